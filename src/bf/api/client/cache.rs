@@ -0,0 +1,528 @@
+// Copyright (c) 2018 Blackfynn, Inc. All Rights Reserved.
+
+//! A local cache of metadata fetched from the platform.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use log::debug;
+use serde_derive::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use crate::bf::Result;
+
+/// The kinds of metadata that can be cached.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum CachedResource {
+    /// The listing of `Blackfynn::get_datasets`.
+    Datasets,
+    /// Datasets fetched by `Blackfynn::get_dataset_by_id`.
+    Dataset,
+    /// Organization members fetched by `Blackfynn::get_members`. Members
+    /// joining by accepting an invitation show up once the entry expires.
+    Members,
+    /// The listing of `Blackfynn::get_organizations`.
+    Organizations,
+}
+
+impl CachedResource {
+    pub fn as_str(&self) -> &str {
+        match self {
+            CachedResource::Datasets => "datasets",
+            CachedResource::Dataset => "dataset",
+            CachedResource::Members => "members",
+            CachedResource::Organizations => "organizations",
+        }
+    }
+
+    /// The time entries of the resource are kept, unless set otherwise.
+    pub fn default_ttl(&self) -> Duration {
+        match self {
+            CachedResource::Datasets | CachedResource::Dataset => Duration::from_secs(60),
+            CachedResource::Members | CachedResource::Organizations => Duration::from_secs(300),
+        }
+    }
+}
+
+impl fmt::Display for CachedResource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// Options for `Blackfynn::enable_cache`.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct CacheOptions {
+    ttls: HashMap<CachedResource, Duration>,
+    disk_dir: Option<PathBuf>,
+}
+
+impl CacheOptions {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Set the time entries of a resource are kept for. A zero TTL
+    /// disables caching of the resource.
+    pub fn with_ttl(mut self, resource: CachedResource, ttl: Duration) -> Self {
+        self.ttls.insert(resource, ttl);
+        self
+    }
+
+    /// Also keep entries in a directory, so they outlive the client. The
+    /// directory should not be shared between users.
+    pub fn with_disk_dir<P: AsRef<Path>>(mut self, dir: P) -> Self {
+        self.disk_dir = Some(dir.as_ref().to_path_buf());
+        self
+    }
+
+    pub fn ttl(&self, resource: CachedResource) -> Duration {
+        self.ttls
+            .get(&resource)
+            .cloned()
+            .unwrap_or_else(|| resource.default_ttl())
+    }
+
+    pub fn disk_dir(&self) -> Option<&PathBuf> {
+        self.disk_dir.as_ref()
+    }
+}
+
+/// The number of lookups answered by the cache, and the number that went
+/// to the platform.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct CacheStats {
+    hits: u64,
+    misses: u64,
+}
+
+impl CacheStats {
+    pub fn hits(&self) -> u64 {
+        self.hits
+    }
+
+    pub fn misses(&self) -> u64 {
+        self.misses
+    }
+}
+
+/// The validators (`ETag` and `Last-Modified`) of a cached response, used
+/// to revalidate the entry once it expires.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct Validators {
+    pub(super) etag: Option<String>,
+    pub(super) last_modified: Option<String>,
+}
+
+impl Validators {
+    fn is_empty(&self) -> bool {
+        self.etag.is_none() && self.last_modified.is_none()
+    }
+}
+
+// A cached response, as stored on disk:
+#[derive(Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DiskEntry {
+    stored_at: u64,
+    value: Value,
+    #[serde(default)]
+    validators: Validators,
+}
+
+struct Entry {
+    stored_at: SystemTime,
+    value: Value,
+    validators: Validators,
+}
+
+impl Entry {
+    fn is_fresh(&self, ttl: Duration) -> bool {
+        self.stored_at
+            .elapsed()
+            .map(|age| age < ttl)
+            .unwrap_or(false)
+    }
+}
+
+const RESOURCES: [CachedResource; 4] = [
+    CachedResource::Datasets,
+    CachedResource::Dataset,
+    CachedResource::Members,
+    CachedResource::Organizations,
+];
+
+/// Responses of the platform, keyed by resource and ID, stored in memory
+/// and optionally on disk. Entries belong to a scope, the session they
+/// were fetched in, and are only returned within that scope.
+pub(super) struct MetadataCache {
+    options: CacheOptions,
+    scope: String,
+    entries: HashMap<(CachedResource, String), Entry>,
+    stats: CacheStats,
+}
+
+impl MetadataCache {
+    pub(super) fn new(options: CacheOptions) -> Result<Self> {
+        if let Some(dir) = options.disk_dir() {
+            fs::create_dir_all(dir)?;
+        }
+        Ok(Self {
+            options,
+            scope: scope_digest(""),
+            entries: HashMap::new(),
+            stats: Default::default(),
+        })
+    }
+
+    pub(super) fn stats(&self) -> CacheStats {
+        self.stats
+    }
+
+    /// Switch to the entries of another scope. Entries of the previous
+    /// scope are dropped from memory, but kept on disk.
+    pub(super) fn set_scope(&mut self, scope: &str) {
+        let scope = scope_digest(scope);
+        if scope != self.scope {
+            self.scope = scope;
+            self.entries.clear();
+        }
+    }
+
+    // The file an entry is kept in on disk. IDs are reduced to characters
+    // that are safe in file names.
+    fn disk_path(&self, resource: CachedResource, key: &str) -> Option<PathBuf> {
+        let key: String = key
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '-' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        self.options
+            .disk_dir()
+            .map(|dir| dir.join(format!("{}-{}-{}.json", self.scope, resource, key)))
+    }
+
+    fn read_disk(&self, resource: CachedResource, key: &str) -> Option<Entry> {
+        let path = self.disk_path(resource, key)?;
+        let contents = fs::read(path).ok()?;
+        let entry: DiskEntry = serde_json::from_slice(&contents).ok()?;
+        Some(Entry {
+            stored_at: UNIX_EPOCH + Duration::from_secs(entry.stored_at),
+            value: entry.value,
+            validators: entry.validators,
+        })
+    }
+
+    fn write_disk(&self, resource: CachedResource, key: &str, entry: &Entry) -> Result<()> {
+        if let Some(path) = self.disk_path(resource, key) {
+            let stored_at = entry
+                .stored_at
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0);
+            let contents = serde_json::to_vec(&DiskEntry {
+                stored_at,
+                value: entry.value.clone(),
+                validators: entry.validators.clone(),
+            })?;
+            fs::write(path, contents)?;
+        }
+        Ok(())
+    }
+
+    /// Look up a fresh entry, first in memory and then on disk. Expired
+    /// entries are kept, so they can be revalidated.
+    pub(super) fn get(&mut self, resource: CachedResource, key: &str) -> Option<Value> {
+        let ttl = self.options.ttl(resource);
+        let id = (resource, key.to_string());
+
+        if !self.entries.get(&id).is_some_and(|e| e.is_fresh(ttl)) {
+            if let Some(entry) = self.read_disk(resource, key) {
+                if self
+                    .entries
+                    .get(&id)
+                    .is_none_or(|e| e.stored_at < entry.stored_at)
+                {
+                    self.entries.insert(id.clone(), entry);
+                }
+            }
+        }
+
+        match self.entries.get(&id).filter(|e| e.is_fresh(ttl)) {
+            Some(entry) => {
+                self.stats.hits += 1;
+                Some(entry.value.clone())
+            }
+            None => {
+                self.stats.misses += 1;
+                None
+            }
+        }
+    }
+
+    /// The validators of an entry, expired or not, if the response it
+    /// holds had any.
+    pub(super) fn validators(&self, resource: CachedResource, key: &str) -> Option<Validators> {
+        self.entries
+            .get(&(resource, key.to_string()))
+            .map(|e| e.validators.clone())
+            .filter(|v| !v.is_empty())
+    }
+
+    pub(super) fn insert(
+        &mut self,
+        resource: CachedResource,
+        key: &str,
+        value: Value,
+        validators: Validators,
+    ) {
+        if self.options.ttl(resource) == Duration::from_secs(0) {
+            return;
+        }
+        self.store(
+            resource,
+            key,
+            Entry {
+                stored_at: SystemTime::now(),
+                value,
+                validators,
+            },
+        );
+    }
+
+    /// Keep an entry for another TTL, once the platform confirmed it did
+    /// not change. Returns the value of the entry, if it is still held.
+    pub(super) fn revalidate(&mut self, resource: CachedResource, key: &str) -> Option<Value> {
+        let mut entry = self.entries.remove(&(resource, key.to_string()))?;
+        entry.stored_at = SystemTime::now();
+        let value = entry.value.clone();
+        self.store(resource, key, entry);
+        Some(value)
+    }
+
+    fn store(&mut self, resource: CachedResource, key: &str, entry: Entry) {
+        // The disk layer is best effort; the entry is still kept in memory:
+        if let Err(err) = self.write_disk(resource, key, &entry) {
+            debug!("bf:cache:write<{}:{}> = {}", resource, key, err);
+        }
+        self.entries.insert((resource, key.to_string()), entry);
+    }
+
+    /// Drop the entry of a resource with the given ID.
+    pub(super) fn invalidate(&mut self, resource: CachedResource, key: &str) {
+        self.entries.remove(&(resource, key.to_string()));
+        if let Some(path) = self.disk_path(resource, key) {
+            let _ = fs::remove_file(path);
+        }
+    }
+
+    /// Drop every entry of a resource in the current scope.
+    pub(super) fn invalidate_all(&mut self, resource: CachedResource) {
+        self.entries.retain(|(r, _), _| *r != resource);
+        let prefix = format!("{}-{}-", self.scope, resource);
+        self.remove_disk_entries(|name| name.starts_with(&prefix));
+    }
+
+    /// Drop every entry, of every scope.
+    pub(super) fn clear(&mut self) {
+        self.entries.clear();
+        // Entries are named "<scope>-<resource>-<key>.json":
+        self.remove_disk_entries(|name| {
+            name.ends_with(".json")
+                && name
+                    .split('-')
+                    .nth(1)
+                    .is_some_and(|resource| RESOURCES.iter().any(|r| r.as_str() == resource))
+        });
+    }
+
+    fn remove_disk_entries<F: Fn(&str) -> bool>(&self, matches: F) {
+        if let Some(dir) = self.options.disk_dir() {
+            if let Ok(files) = fs::read_dir(dir) {
+                for file in files.filter_map(|f| f.ok()) {
+                    if matches(&file.file_name().to_string_lossy()) {
+                        let _ = fs::remove_file(file.path());
+                    }
+                }
+            }
+        }
+    }
+}
+
+// Scopes are kept on disk as a digest, as they name who is logged in:
+fn scope_digest(scope: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.input(scope.as_bytes());
+    format!("{:x}", hasher.result())[..16].to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cache_dir() -> PathBuf {
+        std::env::temp_dir().join(format!("bf-cache-{}", rand::random::<u64>()))
+    }
+
+    #[test]
+    fn entries_are_counted_expired_and_invalidated() {
+        let mut cache = MetadataCache::new(
+            CacheOptions::new().with_ttl(CachedResource::Members, Duration::from_secs(0)),
+        )
+        .unwrap();
+
+        assert_eq!(cache.get(CachedResource::Dataset, "N:dataset:1"), None);
+        cache.insert(
+            CachedResource::Dataset,
+            "N:dataset:1",
+            serde_json::json!(1),
+            Validators::default(),
+        );
+        assert_eq!(
+            cache.get(CachedResource::Dataset, "N:dataset:1"),
+            Some(serde_json::json!(1))
+        );
+
+        cache.insert(
+            CachedResource::Members,
+            "N:organization:1",
+            serde_json::json!([]),
+            Validators::default(),
+        );
+        assert_eq!(cache.get(CachedResource::Members, "N:organization:1"), None);
+
+        cache.invalidate_all(CachedResource::Dataset);
+        assert_eq!(cache.get(CachedResource::Dataset, "N:dataset:1"), None);
+
+        assert_eq!(cache.stats().hits(), 1);
+        assert_eq!(cache.stats().misses(), 3);
+    }
+
+    #[test]
+    fn entries_outlive_the_cache_on_disk() {
+        let dir = cache_dir();
+        let options = CacheOptions::new().with_disk_dir(&dir);
+
+        let mut cache = MetadataCache::new(options.clone()).unwrap();
+        cache.insert(
+            CachedResource::Datasets,
+            "",
+            serde_json::json!(["a"]),
+            Validators::default(),
+        );
+        cache.insert(
+            CachedResource::Dataset,
+            "N:dataset:1",
+            serde_json::json!("b"),
+            Validators::default(),
+        );
+
+        let mut cache = MetadataCache::new(options.clone()).unwrap();
+        assert_eq!(
+            cache.get(CachedResource::Datasets, ""),
+            Some(serde_json::json!(["a"]))
+        );
+
+        cache.invalidate(CachedResource::Dataset, "N:dataset:1");
+        let mut cache = MetadataCache::new(options).unwrap();
+        assert_eq!(cache.get(CachedResource::Dataset, "N:dataset:1"), None);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn expired_entries_are_revalidated() {
+        let dir = cache_dir();
+        let options = CacheOptions::new()
+            .with_ttl(CachedResource::Dataset, Duration::from_millis(10))
+            .with_disk_dir(&dir);
+        let validators = Validators {
+            etag: Some("\"v1\"".to_string()),
+            last_modified: None,
+        };
+
+        let mut cache = MetadataCache::new(options.clone()).unwrap();
+        cache.insert(
+            CachedResource::Dataset,
+            "N:dataset:1",
+            serde_json::json!(1),
+            validators.clone(),
+        );
+        std::thread::sleep(Duration::from_millis(20));
+
+        let mut cache = MetadataCache::new(options).unwrap();
+        assert_eq!(cache.get(CachedResource::Dataset, "N:dataset:1"), None);
+        assert_eq!(
+            cache.validators(CachedResource::Dataset, "N:dataset:1"),
+            Some(validators)
+        );
+        assert_eq!(
+            cache.revalidate(CachedResource::Dataset, "N:dataset:1"),
+            Some(serde_json::json!(1))
+        );
+        assert_eq!(
+            cache.get(CachedResource::Dataset, "N:dataset:1"),
+            Some(serde_json::json!(1))
+        );
+
+        cache.clear();
+        assert_eq!(
+            cache.validators(CachedResource::Dataset, "N:dataset:1"),
+            None
+        );
+        assert_eq!(
+            cache.revalidate(CachedResource::Dataset, "N:dataset:1"),
+            None
+        );
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn entries_are_kept_apart_by_scope() {
+        let dir = cache_dir();
+        let options = CacheOptions::new().with_disk_dir(&dir);
+
+        let mut cache = MetadataCache::new(options.clone()).unwrap();
+        cache.set_scope("alice/N:organization:1");
+        cache.insert(
+            CachedResource::Datasets,
+            "",
+            serde_json::json!(["a"]),
+            Validators::default(),
+        );
+
+        cache.set_scope("bob/N:organization:1");
+        assert_eq!(cache.get(CachedResource::Datasets, ""), None);
+        cache.insert(
+            CachedResource::Datasets,
+            "",
+            serde_json::json!(["b"]),
+            Validators::default(),
+        );
+
+        let mut cache = MetadataCache::new(options.clone()).unwrap();
+        cache.set_scope("alice/N:organization:1");
+        assert_eq!(
+            cache.get(CachedResource::Datasets, ""),
+            Some(serde_json::json!(["a"]))
+        );
+
+        cache.clear();
+        cache.set_scope("bob/N:organization:1");
+        assert_eq!(cache.get(CachedResource::Datasets, ""), None);
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 0);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,83 @@
+// Copyright (c) 2018 Blackfynn, Inc. All Rights Reserved.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use crate::bf::{Error, Result};
+
+#[derive(Debug, Default)]
+struct CancellationState {
+    cancelled: AtomicBool,
+    abort: AtomicBool,
+}
+
+/// A handle used to cancel a long running operation, such as an upload,
+/// a download or a paged read.
+///
+/// Clones of a token share their state, so a token can be handed to an
+/// operation and cancelled from elsewhere. Cancelling stops new work
+/// from being scheduled; work already in flight is allowed to settle.
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken {
+    state: Arc<CancellationState>,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Request cancellation. A cancelled upload can be resumed later.
+    pub fn cancel(&self) {
+        self.state.cancelled.store(true, Ordering::SeqCst);
+    }
+
+    /// Request cancellation, additionally aborting the import on the
+    /// upload service. An aborted upload cannot be resumed.
+    pub fn cancel_and_abort(&self) {
+        self.state.abort.store(true, Ordering::SeqCst);
+        self.cancel();
+    }
+
+    /// Tests if cancellation was requested.
+    pub fn is_cancelled(&self) -> bool {
+        self.state.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// Tests if aborting the import was requested along with cancellation.
+    pub fn is_abort_requested(&self) -> bool {
+        self.state.abort.load(Ordering::SeqCst)
+    }
+
+    /// Returns `ErrorKind::Cancelled` if cancellation was requested.
+    pub fn check(&self) -> Result<()> {
+        if self.is_cancelled() {
+            Err(Error::cancelled())
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bf::ErrorKind;
+
+    #[test]
+    fn clones_share_cancellation() {
+        let token = CancellationToken::new();
+        let clone = token.clone();
+        assert!(clone.check().is_ok());
+
+        token.cancel();
+        assert!(clone.is_cancelled());
+        assert!(!clone.is_abort_requested());
+        assert_eq!(clone.check().unwrap_err().kind(), &ErrorKind::Cancelled);
+
+        let token = CancellationToken::new();
+        token.cancel_and_abort();
+        assert!(token.is_cancelled());
+        assert!(token.is_abort_requested());
+    }
+}
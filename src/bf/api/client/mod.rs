@@ -1,18 +1,30 @@
 //! Functions to interact with the Blackfynn platform.
 
+pub mod cache;
+pub mod cancellation;
 pub mod progress;
+pub mod report;
+pub mod schema;
+pub mod streaming;
 
+use self::cache::{CacheOptions, CacheStats, CachedResource, MetadataCache, Validators};
+use self::cancellation::CancellationToken;
+use self::progress::ProgressEvent;
 pub use self::progress::{ProgressCallback, ProgressUpdate};
+use self::report::{FileReport, FileStatus, UploadReport};
+use self::schema::RecordSchema;
+use self::streaming::{StreamingOptions, TimeseriesWriter};
 
 use std::borrow::Borrow;
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
+use std::fs;
 use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
-use std::{iter, time};
+use std::{iter, result, time};
 
 use futures::{Future as _Future, Stream as _Stream, *};
 use hyper::client::{Client, HttpConnector};
-use hyper::header::{HeaderName, HeaderValue};
+use hyper::header::{HeaderMap, HeaderName, HeaderValue};
 use hyper::{self, Method, StatusCode};
 use hyper_tls::HttpsConnector;
 use lazy_static::lazy_static;
@@ -21,13 +33,14 @@ use serde;
 use serde_json;
 use tokio;
 
-use super::request::chunked_http::ChunkedFilePayload;
+use super::request::chunked_http::{ChunkedFilePayload, FileChecksums, DEFAULT_CHUNK_SIZE_BYTES};
 use super::{request, response};
 use crate::bf::config::{Config, Environment};
 use crate::bf::model::upload::MultipartUploadId;
 use crate::bf::model::{
-    self, DatasetId, DatasetNodeId, FileUpload, ImportId, OrganizationId, PackageId, SessionToken,
-    UploadId,
+    self, AnnotationId, AnnotationLayerId, ChannelId, ConceptId, DatasetId, DatasetNodeId,
+    DatasetStatus, FileUpload, ImportId, OrganizationId, PackageId, RecordId, RelationshipId, Role,
+    SessionToken, TeamId, UploadId, UserId,
 };
 use crate::bf::util::futures::{into_future_trait, into_stream_trait};
 use crate::bf::{Error, ErrorKind, Future, Result, Stream};
@@ -37,6 +50,12 @@ const X_SESSION_ID: &str = "X-SESSION-ID";
 
 const MAX_RETRIES: usize = 20;
 
+// The number of activity events fetched per request:
+const ACTIVITY_PAGE_SIZE: usize = 100;
+
+// The duration of the pages timeseries data is fetched in, in microseconds:
+const DEFAULT_TIMESERIES_PAGE_MICROS: i64 = 10_000_000;
+
 lazy_static! {
     static ref ALL_METHODS: Vec<Method> = vec![
         Method::GET,
@@ -90,7 +109,29 @@ struct BlackFynnImpl {
     config: Config,
     http_client: Client<HttpsConnector<HttpConnector>>,
     session_token: Option<SessionToken>,
+    // The API key the session was created with, or the session token if it
+    // was set directly:
+    session_key: Option<String>,
     current_organization: Option<OrganizationId>,
+    cache: Option<MetadataCache>,
+}
+
+impl BlackFynnImpl {
+    // Cached entries are kept apart per user and organization, so a change
+    // of either never returns the entries of the previous session:
+    fn rescope_cache(&mut self) {
+        let scope = format!(
+            "{}/{}",
+            self.session_key.as_deref().unwrap_or(""),
+            self.current_organization
+                .as_ref()
+                .map(String::from)
+                .unwrap_or_default()
+        );
+        if let Some(cache) = self.cache.as_mut() {
+            cache.set_scope(&scope);
+        }
+    }
 }
 
 /// The Blackfynn client.
@@ -113,6 +154,12 @@ impl Clone for Blackfynn {
 // Request parameter
 type RequestParam = (String, String);
 
+// The status code, headers and body of a response:
+type RawResponse = (StatusCode, HeaderMap, hyper::Chunk);
+
+// The collection found at a path, and its children. See `get_children_at_path`.
+type CollectionListing = Option<(Option<PackageId>, Vec<response::Package>)>;
+
 // A useful alias when dealing with the fact that an endpoint does not expect
 // a POST/PUT body, but a type is still expected:
 type Nothing = serde_json::Value;
@@ -201,6 +248,148 @@ macro_rules! delete {
     };
 }
 
+/// Split a `/`-separated package path into its non-empty components.
+fn path_components(path: &str) -> Vec<String> {
+    path.split('/')
+        .filter(|component| !component.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+/// Check whether a looked up package can be deleted. Unless `recursive` is
+/// set, collections that still contain packages cannot.
+fn deletable(
+    id: PackageId,
+    lookup: Result<response::Package>,
+    recursive: bool,
+) -> result::Result<response::Package, response::DeleteFailure> {
+    match lookup {
+        Ok(ref package)
+            if package.is_collection()
+                && package.children().is_some_and(|c| !c.is_empty())
+                && !recursive =>
+        {
+            Err(response::DeleteFailure::new(id, "collection is not empty"))
+        }
+        Ok(package) => Ok(package),
+        Err(err) => Err(response::DeleteFailure::new(id, err.to_string())),
+    }
+}
+
+/// Type the values of decoded records by the schema of their concept.
+fn with_schema(
+    records: Vec<model::Record>,
+    schema: &[model::ConceptProperty],
+) -> Result<Vec<model::Record>> {
+    records
+        .into_iter()
+        .map(|record| record.with_schema(schema))
+        .collect()
+}
+
+/// Look up the IDs of the users with the given email addresses, ignoring
+/// case. Every address must belong to one of `users`, the members of a
+/// `group` such as an organization.
+fn user_ids_by_email(users: &[model::User], emails: &[String], group: &str) -> Result<Vec<UserId>> {
+    emails
+        .iter()
+        .map(|email| {
+            users
+                .iter()
+                .find(|user| user.email().eq_ignore_ascii_case(email))
+                .map(|user| user.id().clone())
+                .ok_or_else(|| {
+                    Error::invalid_arguments(format!(
+                        "no member of the {} has the email address {}",
+                        group, email
+                    ))
+                })
+        })
+        .collect()
+}
+
+/// The page of the time range `[page_start, end)` starting at
+/// `page_start` and lasting at most `page_duration` microseconds, or
+/// `None` once the range is exhausted.
+fn next_timeseries_page(page_start: i64, end: i64, page_duration: i64) -> Option<(i64, i64)> {
+    if page_start < end {
+        Some((
+            page_start,
+            page_start.saturating_add(page_duration).min(end),
+        ))
+    } else {
+        None
+    }
+}
+
+/// The value of a response header, if it is set and is valid text.
+fn header_string(headers: &HeaderMap, name: HeaderName) -> Option<String> {
+    headers
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .map(String::from)
+}
+
+/// The path a file of an upload is reported under in progress events:
+/// its path within the upload, under the directory passed to the upload
+/// functions. Unlike its name, this is unique within an import.
+fn progress_path(path: &Path, file: &model::S3File) -> PathBuf {
+    path.join(file.relative_path())
+}
+
+/// The payload sending the chunks of a file of an upload, skipping the
+/// parts the upload service already received.
+fn file_payload(
+    import_id: &ImportId,
+    path: &Path,
+    file: &model::S3File,
+    missing_parts: Option<&response::FilesMissingParts>,
+    checksums: FileChecksums,
+) -> ChunkedFilePayload {
+    let file_path = path.join(file.file_name());
+    let file_missing_parts: Option<&response::FileMissingParts> =
+        missing_parts.and_then(|mp| mp.files.iter().find(|p| &p.file_name == file.file_name()));
+
+    if let Some(chunked_upload_properties) = file.chunked_upload() {
+        debug!(
+            "bf:upload_file_chunks<file = {file_name}> :: \
+             Chunk size received from the upload service: {chunk_size}.",
+            file_name = file.file_name(),
+            chunk_size = chunked_upload_properties.chunk_size
+        );
+
+        ChunkedFilePayload::new_with_chunk_size(
+            import_id.clone(),
+            file_path,
+            chunked_upload_properties.chunk_size,
+            file_missing_parts,
+        )
+    } else {
+        debug!(
+            "bf:upload_file_chunks<file = {file_name}> :: \
+             No chunk size received from the upload service. \
+             Falling back to default.",
+            file_name = file.file_name()
+        );
+        ChunkedFilePayload::new(import_id.clone(), file_path, file_missing_parts)
+    }
+    .with_checksums(checksums, file.relative_path())
+    .with_progress_path(progress_path(path, file))
+}
+
+/// Fail with `ErrorKind::Forbidden` unless the current user is an
+/// administrator of the organization.
+fn require_admin(organization: &response::Organization) -> Result<()> {
+    if organization.is_admin() {
+        Ok(())
+    } else {
+        Err(Error::forbidden(format!(
+            "administrator privileges are required for organization {}",
+            organization.organization().id()
+        )))
+    }
+}
+
 // ============================================================================s
 
 impl Blackfynn {
@@ -213,7 +402,9 @@ impl Blackfynn {
                 config,
                 http_client,
                 session_token: None,
+                session_key: None,
                 current_organization: None,
+                cache: None,
             })),
         }
     }
@@ -231,6 +422,110 @@ impl Blackfynn {
         self.inner.lock().unwrap().config.env().url().clone()
     }
 
+    /// Make a GET request to the given route, answering it from the
+    /// metadata cache when enabled and holding a fresh entry for `key`.
+    /// An expired entry is revalidated with `If-None-Match` and
+    /// `If-Modified-Since`, and kept if the platform answers with a 304.
+    fn cached_get<Q>(&self, resource: CachedResource, key: String, route: String) -> Future<Q>
+    where
+        Q: 'static + Send + serde::de::DeserializeOwned,
+    {
+        let lookup = self
+            .inner
+            .lock()
+            .unwrap()
+            .cache
+            .as_mut()
+            .map(|cache| (cache.get(resource, &key), cache.validators(resource, &key)));
+
+        let value: Future<serde_json::Value> = match lookup {
+            Some((Some(value), _)) => into_future_trait(future::ok(value)),
+            Some((None, validators)) => {
+                let mut headers = vec![];
+                if let Some(validators) = validators {
+                    let etag = validators.etag.and_then(|v| HeaderValue::from_str(&v).ok());
+                    if let Some(etag) = etag {
+                        headers.push((hyper::header::IF_NONE_MATCH, etag));
+                    }
+                    let last_modified = validators
+                        .last_modified
+                        .and_then(|v| HeaderValue::from_str(&v).ok());
+                    if let Some(last_modified) = last_modified {
+                        headers.push((hyper::header::IF_MODIFIED_SINCE, last_modified));
+                    }
+                }
+
+                let bf = self.clone();
+                let f = self
+                    .raw_request(route.clone(), Method::GET, params!(), vec![], headers, true)
+                    .and_then(move |(status_code, headers, body)| {
+                        let mut inner = bf.inner.lock().unwrap();
+                        if status_code == StatusCode::NOT_MODIFIED {
+                            // The entry may have been dropped while revalidating it:
+                            let value = inner
+                                .cache
+                                .as_mut()
+                                .and_then(|cache| cache.revalidate(resource, &key));
+                            drop(inner);
+                            match value {
+                                Some(value) => into_future_trait(future::ok(value)),
+                                None => get!(bf, route),
+                            }
+                        } else {
+                            let value: serde_json::Value = match Self::parse_json(&body) {
+                                Ok(value) => value,
+                                Err(err) => return into_future_trait(future::err(err)),
+                            };
+                            if let Some(cache) = inner.cache.as_mut() {
+                                let validators = Validators {
+                                    etag: header_string(&headers, hyper::header::ETAG),
+                                    last_modified: header_string(
+                                        &headers,
+                                        hyper::header::LAST_MODIFIED,
+                                    ),
+                                };
+                                cache.insert(resource, &key, value.clone(), validators);
+                            }
+                            into_future_trait(future::ok(value))
+                        }
+                    });
+                into_future_trait(f)
+            }
+            None => get!(self, route),
+        };
+
+        into_future_trait(value.and_then(|value| serde_json::from_value(value).map_err(Into::into)))
+    }
+
+    /// Drop cached entries of a resource: the entry for `key`, or every
+    /// entry if no key is given.
+    fn invalidate_cached(&self, resource: CachedResource, key: Option<&str>) {
+        if let Some(cache) = self.inner.lock().unwrap().cache.as_mut() {
+            match key {
+                Some(key) => cache.invalidate(resource, key),
+                None => cache.invalidate_all(resource),
+            }
+        }
+    }
+
+    /// Drop the cached listing of datasets, along with the cached dataset
+    /// with the given ID, or every cached dataset if the dataset is not
+    /// known. Called after every change to a dataset or its packages.
+    fn invalidate_dataset(&self, id: Option<&DatasetNodeId>) {
+        let key = id.map(String::from);
+        self.invalidate_cached(CachedResource::Datasets, None);
+        self.invalidate_cached(CachedResource::Dataset, key.as_deref());
+    }
+
+    /// Drop the cached listing of organizations, along with the cached
+    /// members of the given organization, or of every organization if no
+    /// organization is given. Called after every change to a membership.
+    fn invalidate_organization(&self, id: Option<&OrganizationId>) {
+        let key = id.map(String::from);
+        self.invalidate_cached(CachedResource::Organizations, None);
+        self.invalidate_cached(CachedResource::Members, key.as_deref());
+    }
+
     /// Make a request to the given route using the given json payload
     /// as a request body. This function will automatically retry if
     /// it receives a 429 response (rate limit exceeded) from the
@@ -314,11 +609,62 @@ impl Blackfynn {
         I: IntoIterator<Item = RequestParam>,
         Q: 'static + Send + serde::de::DeserializeOwned,
         S: Into<String>,
+    {
+        let f = self
+            .raw_request(
+                route,
+                method,
+                params,
+                body,
+                additional_headers,
+                retry_on_failure,
+            )
+            .and_then(|(_, _, body)| Self::parse_json(&body));
+
+        into_future_trait(f)
+    }
+
+    /// Attempt to parse a JSON response into a typeful representation.
+    /// serde_json::from_slice will fail if the response body is empty, so
+    /// the empty body is treated as a valid "null" json string.
+    fn parse_json<Q: serde::de::DeserializeOwned>(body: &[u8]) -> Result<Q> {
+        let body = if body.is_empty() { &b"null"[..] } else { body };
+        serde_json::from_slice(body).map_err(Into::into)
+    }
+
+    /// The error for an unsuccessful response. A 412 response means a
+    /// precondition such as `If-Match` failed, and is reported as a
+    /// conflict.
+    fn response_error(status_code: StatusCode, body: &[u8]) -> Error {
+        let message = String::from_utf8_lossy(body);
+        if status_code == StatusCode::PRECONDITION_FAILED {
+            Error::conflict(message)
+        } else {
+            Error::api_error(status_code, message)
+        }
+    }
+
+    /// Make a request to the given route, as `request_with_body` does,
+    /// but return the status code, headers and body of the response
+    /// without parsing it. Unsuccessful responses are still turned into
+    /// errors.
+    fn raw_request<I, S>(
+        &self,
+        route: S,
+        method: Method,
+        params: I,
+        body: Vec<u8>,
+        additional_headers: Vec<(HeaderName, HeaderValue)>,
+        retry_on_failure: bool,
+    ) -> Future<RawResponse>
+    where
+        I: IntoIterator<Item = RequestParam>,
+        S: Into<String>,
     {
         let route: String = route.into();
         let params: Vec<RequestParam> = params.into_iter().collect();
 
-        let response = if retry_on_failure {
+        if retry_on_failure {
             //  A retry state object that is threaded through the
             //  retry loop in order to track state
             struct RetryState {
@@ -351,7 +697,7 @@ impl Blackfynn {
                         retry_state.body.clone().into(),
                         retry_state.additional_headers.clone(),
                     )
-                    .and_then(|(status_code, body)| {
+                    .and_then(|(status_code, headers, body)| {
                         // if the status code is considered retryable, wait for a few seconds and
                         // restart the loop to retry again.
                         match RETRYABLE_STATUS_CODES.get(&status_code) {
@@ -361,9 +707,9 @@ impl Blackfynn {
                                 retry_state.try_num += 1;
 
                                 if retry_state.try_num > MAX_RETRIES {
-                                    into_future_trait(future::err(Error::api_error(
+                                    into_future_trait(future::err(Self::response_error(
                                         status_code,
-                                        String::from_utf8_lossy(&body),
+                                        &body,
                                     )))
                                 } else {
                                     let delay = retry_delay(retry_state.try_num);
@@ -378,12 +724,16 @@ impl Blackfynn {
                                 }
                             }
                             _ if status_code.is_client_error() || status_code.is_server_error() => {
-                                into_future_trait(future::err(Error::api_error(
+                                into_future_trait(future::err(Self::response_error(
                                     status_code,
-                                    String::from_utf8_lossy(&body),
+                                    &body,
                                 )))
                             }
-                            _ => into_future_trait(future::ok(future::Loop::Break(body))),
+                            _ => into_future_trait(future::ok(future::Loop::Break((
+                                status_code,
+                                headers,
+                                body,
+                            )))),
                         }
                     })
             });
@@ -397,34 +747,69 @@ impl Blackfynn {
                     body.into(),
                     additional_headers.clone(),
                 )
-                .and_then(|(status_code, body)| {
+                .and_then(|(status_code, headers, body)| {
                     if status_code.is_client_error() || status_code.is_server_error() {
-                        future::err(Error::api_error(
-                            status_code,
-                            String::from_utf8_lossy(&body),
-                        ))
+                        future::err(Self::response_error(status_code, &body))
                     } else {
-                        future::ok(body)
+                        future::ok((status_code, headers, body))
                     }
                 });
             into_future_trait(f)
+        }
+    }
+
+    /// Make a PUT request to the given route with a json payload, sending
+    /// `etag` as `If-Match`, so the update fails with `ErrorKind::Conflict`
+    /// if the resource was changed since. The request is never retried:
+    /// a retry of an update that was applied would fail the precondition.
+    fn conditional_put<P, Q>(&self, route: String, payload: &P, etag: String) -> Future<Q>
+    where
+        P: serde::Serialize,
+        Q: 'static + Send + serde::de::DeserializeOwned,
+    {
+        let body = match serde_json::to_vec(payload) {
+            Ok(body) => body,
+            Err(err) => return into_future_trait(future::err(err.into())),
+        };
+        let etag = match HeaderValue::from_str(&etag) {
+            Ok(etag) => etag,
+            Err(_) => {
+                return into_future_trait(future::err(Error::invalid_arguments(format!(
+                    "not a valid ETag: {}",
+                    etag
+                ))))
+            }
         };
 
-        // Finally, attempt to parse the JSON response into a typeful
-        // representation. serde_json::from_slice will fail if the
-        // response body is empty, so we need to convert the empty
-        // body into a valid "null" json string in that case.
-        let json = response.and_then(|chunk| {
-            let bytes = chunk.into_bytes();
-            let bytes = if bytes.is_empty() {
-                b"null"[..].into()
-            } else {
-                bytes
-            };
-            serde_json::from_slice(&bytes).map_err(Into::into)
-        });
+        let headers = vec![
+            (
+                hyper::header::CONTENT_TYPE,
+                HeaderValue::from_static("application/json"),
+            ),
+            (hyper::header::IF_MATCH, etag),
+        ];
+
+        let f = self
+            .raw_request(route, Method::PUT, params!(), body, headers, false)
+            .and_then(|(_, _, body)| Self::parse_json(&body));
+
+        into_future_trait(f)
+    }
+
+    /// Make a GET request to the given route, returning the response along
+    /// with its `ETag`, if it has one.
+    fn get_with_etag<Q>(&self, route: String) -> Future<(Q, Option<String>)>
+    where
+        Q: 'static + Send + serde::de::DeserializeOwned,
+    {
+        let f = self
+            .raw_request(route, Method::GET, params!(), vec![], vec![], true)
+            .and_then(|(_, headers, body)| {
+                Self::parse_json(&body)
+                    .map(|value| (value, header_string(&headers, hyper::header::ETAG)))
+            });
 
-        into_future_trait(json)
+        into_future_trait(f)
     }
 
     /// Make a single request to the platform. This function is used
@@ -439,6 +824,8 @@ impl Blackfynn {
     /// * `method` - The HTTP method
     /// * `body` - A byte array payload
     /// * `additional_headers` - Additional headers to include
+    ///
+    /// Returns the status code, headers and body of the response.
     fn single_request(
         &self,
         route: String,
@@ -446,7 +833,7 @@ impl Blackfynn {
         method: Method,
         body: hyper::Body,
         additional_headers: Vec<(HeaderName, HeaderValue)>,
-    ) -> Future<(StatusCode, hyper::Chunk)> {
+    ) -> Future<RawResponse> {
         let token = self.session_token().clone();
         let client = self.inner.lock().unwrap().http_client.clone();
 
@@ -494,6 +881,7 @@ impl Blackfynn {
                     .map_err(Into::into)
                     .and_then(|response| {
                         let status_code = response.status();
+                        let headers = response.headers().clone();
                         response
                             .into_body()
                             .concat2()
@@ -504,7 +892,7 @@ impl Blackfynn {
                                     url = url,
                                     payload = Self::chunk_to_string(&body)
                                 );
-                                (status_code, body)
+                                (status_code, headers, body)
                             })
                             .map_err(Into::into)
                     })
@@ -513,6 +901,78 @@ impl Blackfynn {
         into_future_trait(f)
     }
 
+    /// Download the contents of an absolute URL, such as a temporary URL
+    /// returned by the platform. No session headers are sent.
+    // The body is read chunk by chunk, so cancellation stops a download
+    // that is still in progress:
+    fn fetch_url(&self, url: String, cancellation: CancellationToken) -> Future<Vec<u8>> {
+        let client = self.inner.lock().unwrap().http_client.clone();
+
+        let f = url
+            .parse::<hyper::Uri>()
+            .map_err(Into::<Error>::into)
+            .into_future()
+            .and_then(move |uri| client.get(uri).map_err(Into::into))
+            .and_then(|response| {
+                let status_code = response.status();
+                response
+                    .into_body()
+                    .map_err(Into::<Error>::into)
+                    .and_then(move |chunk| cancellation.check().map(|_| chunk))
+                    .fold(vec![], |mut body, chunk| {
+                        body.extend_from_slice(&chunk);
+                        future::ok::<_, Error>(body)
+                    })
+                    .and_then(move |body| {
+                        if status_code.is_client_error() || status_code.is_server_error() {
+                            Err(Error::api_error(
+                                status_code,
+                                String::from_utf8_lossy(&body),
+                            ))
+                        } else {
+                            Ok(body)
+                        }
+                    })
+            });
+
+        into_future_trait(f)
+    }
+
+    /// Cache the metadata returned by `get_datasets`, `get_dataset_by_id`,
+    /// `get_members` and `get_organizations`. Entries are dropped once
+    /// their TTL passes, or when changed through this client. Entries are
+    /// kept per user and organization: logging in again or changing the
+    /// current organization switches to the entries of the new session.
+    pub fn enable_cache(&self, options: CacheOptions) -> Result<()> {
+        let mut inner = self.inner.lock().unwrap();
+        inner.cache = Some(MetadataCache::new(options)?);
+        inner.rescope_cache();
+        Ok(())
+    }
+
+    /// Stop caching metadata. Entries kept on disk are left in place.
+    pub fn disable_cache(&self) {
+        self.inner.lock().unwrap().cache = None;
+    }
+
+    /// Drop every cached entry, of every session, including those kept on
+    /// disk.
+    pub fn clear_cache(&self) {
+        if let Some(cache) = self.inner.lock().unwrap().cache.as_mut() {
+            cache.clear();
+        }
+    }
+
+    /// The hit and miss counts of the cache, if enabled.
+    pub fn cache_stats(&self) -> Option<CacheStats> {
+        self.inner
+            .lock()
+            .unwrap()
+            .cache
+            .as_ref()
+            .map(MetadataCache::stats)
+    }
+
     /// Test if the user is logged into the Blackfynn platform.
     pub fn has_session(&self) -> bool {
         self.session_token().is_some()
@@ -525,12 +985,17 @@ impl Blackfynn {
 
     /// Set the current organization the user is associated with.
     pub fn set_current_organization(&self, id: Option<&OrganizationId>) {
-        self.inner.lock().unwrap().current_organization = id.cloned()
+        let mut inner = self.inner.lock().unwrap();
+        inner.current_organization = id.cloned();
+        inner.rescope_cache();
     }
 
     /// Set the session token the user is associated with.
     pub fn set_session_token(&self, token: Option<SessionToken>) {
-        self.inner.lock().unwrap().session_token = token;
+        let mut inner = self.inner.lock().unwrap();
+        inner.session_key = token.clone().map(SessionToken::take);
+        inner.session_token = token;
+        inner.rescope_cache();
     }
 
     /// Set the active environment
@@ -548,19 +1013,87 @@ impl Blackfynn {
         api_key: S,
         api_secret: S,
     ) -> Future<response::ApiSession> {
-        let payload = request::ApiLogin::new(api_key.into(), api_secret.into());
+        let api_key = api_key.into();
+        let payload = request::ApiLogin::new(api_key.clone(), api_secret.into());
         let this = self.clone();
         into_future_trait(
             post!(self, "/account/api/session", params!(), &payload).and_then(
                 move |login_response: response::ApiSession| {
-                    this.inner.lock().unwrap().session_token =
-                        Some(login_response.session_token().clone());
+                    let mut inner = this.inner.lock().unwrap();
+                    inner.session_token = Some(login_response.session_token().clone());
+                    inner.session_key = Some(api_key);
+                    inner.rescope_cache();
                     Ok(login_response)
                 },
             ),
         )
     }
 
+    /// Create an API key for the current user. The secret of the key is
+    /// only ever returned here.
+    pub fn create_api_key<N: Into<String>>(&self, name: N) -> Future<response::ApiKey> {
+        post!(
+            self,
+            "/token/",
+            params!(),
+            payload!(request::CreateApiKey::new(name))
+        )
+    }
+
+    /// List the API keys of the current user.
+    pub fn list_api_keys(&self) -> Future<Vec<response::ApiKey>> {
+        get!(self, "/token/")
+    }
+
+    /// Revoke an API key of the current user.
+    pub fn revoke_api_key<K: Into<String>>(&self, key: K) -> Future<()> {
+        let key = key.into();
+        let f = delete!(self, route!("/token/{key}", key)).map(|_: Nothing| ());
+        into_future_trait(f)
+    }
+
+    /// Replace an API key of the current user with a new one.
+    ///
+    /// The new key is created, used to log in, and checked by fetching the
+    /// current user before `old_key` is revoked. Afterwards, the client is
+    /// logged in with the new key, which is returned along with its
+    /// secret. If any step fails, the new key is revoked, the client is
+    /// returned to its previous session and the old key is left in place.
+    pub fn rotate_api_key<N, K>(&self, name: N, old_key: K) -> Future<response::ApiKey>
+    where
+        N: Into<String>,
+        K: Into<String>,
+    {
+        let bf = self.clone();
+        let old_key = old_key.into();
+        let previous_session = self.session_token();
+
+        let f = self.create_api_key(name).and_then(move |api_key| {
+            let new_key = api_key.key().clone();
+            let rotate: Future<()> = match api_key.secret().cloned() {
+                Some(secret) => {
+                    let bf = bf.clone();
+                    into_future_trait(
+                        bf.login(new_key.clone(), secret)
+                            .and_then(move |_| bf.get_user().map(|_| bf))
+                            .and_then(move |bf| bf.revoke_api_key(old_key)),
+                    )
+                }
+                None => into_future_trait(future::err(ErrorKind::MissingApiKeySecret.into())),
+            };
+
+            rotate.then(move |result| match result {
+                Ok(()) => into_future_trait(future::ok(api_key)),
+                Err(err) => {
+                    bf.set_session_token(previous_session);
+                    into_future_trait(bf.revoke_api_key(new_key).then(move |_| Err(err)))
+                }
+            })
+        });
+
+        into_future_trait(f)
+    }
+
     /// Get the current user.
     pub fn get_user(&self) -> Future<model::User> {
         get!(self, "/user/")
@@ -572,18 +1105,98 @@ impl Blackfynn {
         organization_id: Option<OrganizationId>,
     ) -> Future<model::User> {
         let this = self.clone();
-        let user = request::User::with_organization(organization_id);
-        into_future_trait(put!(self, "/user/", params!(), &user).and_then(
-            move |user_response: model::User| {
-                this.set_current_organization(user_response.preferred_organization());
-                Ok(user_response)
+        let update = request::UserUpdate::new().with_organization(organization_id);
+        into_future_trait(
+            self.update_user(update)
+                .and_then(move |user_response: model::User| {
+                    this.set_current_organization(user_response.preferred_organization());
+                    Ok(user_response)
+                }),
+        )
+    }
+
+    /// Update the profile of the current user. Only the fields set in
+    /// `update` are changed. Unlike `set_preferred_organization`, this does
+    /// not change the organization the client is working in.
+    pub fn update_user(&self, update: request::UserUpdate) -> Future<model::User> {
+        let this = self.clone();
+        into_future_trait(put!(self, "/user/", params!(), &update).then(
+            move |result: Result<model::User>| {
+                // The user is listed among the members of every organization:
+                this.invalidate_organization(None);
+                result
             },
         ))
     }
 
+    /// Change the password of the current user, which requires their
+    /// current password.
+    pub fn change_password<C, N>(&self, current_password: C, new_password: N) -> Future<model::User>
+    where
+        C: Into<String>,
+        N: Into<String>,
+    {
+        put!(
+            self,
+            "/user/",
+            params!(),
+            payload!(request::PasswordChange::new(current_password, new_password))
+        )
+    }
+
+    /// Get a member of an organization by ID.
+    pub fn get_user_by_id(
+        &self,
+        organization_id: OrganizationId,
+        id: UserId,
+    ) -> Future<model::User> {
+        let f = self
+            .get_members_by_organization(organization_id.clone())
+            .and_then(move |members| {
+                members
+                    .into_iter()
+                    .find(|member| member.id() == &id)
+                    .ok_or_else(|| {
+                        Error::invalid_arguments(format!(
+                            "no member of organization {} has the ID {}",
+                            organization_id,
+                            Borrow::<str>::borrow(&id)
+                        ))
+                    })
+            });
+        into_future_trait(f)
+    }
+
+    /// Get a member of an organization by email address.
+    pub fn get_user_by_email<E: Into<String>>(
+        &self,
+        organization_id: OrganizationId,
+        email: E,
+    ) -> Future<model::User> {
+        let email = email.into();
+        let f = self
+            .get_members_by_organization(organization_id.clone())
+            .and_then(move |members| {
+                members
+                    .into_iter()
+                    .find(|member| member.email().eq_ignore_ascii_case(&email))
+                    .ok_or_else(|| {
+                        Error::invalid_arguments(format!(
+                            "no member of organization {} has the email address {}",
+                            organization_id, email
+                        ))
+                    })
+            });
+        into_future_trait(f)
+    }
+
     /// List the organizations the user is a member of.
     pub fn get_organizations(&self) -> Future<response::Organizations> {
-        get!(self, "/organizations/")
+        self.cached_get(
+            CachedResource::Organizations,
+            String::new(),
+            "/organizations/".to_string(),
+        )
     }
 
     /// Get a specific organization.
@@ -593,7 +1206,29 @@ impl Blackfynn {
 
     /// Get a listing of the datasets the current user has access to.
     pub fn get_datasets(&self) -> Future<Vec<response::Dataset>> {
-        get!(self, "/datasets/")
+        self.cached_get(
+            CachedResource::Datasets,
+            String::new(),
+            "/datasets/".to_string(),
+        )
+    }
+
+    /// Get the datasets the current user has access to that have the
+    /// given status.
+    ///
+    /// The datasets are filtered locally, after fetching every dataset
+    /// with `get_datasets`.
+    pub fn get_datasets_with_status(
+        &self,
+        status: DatasetStatus,
+    ) -> Future<Vec<response::Dataset>> {
+        let f = self.get_datasets().map(move |datasets| {
+            datasets
+                .into_iter()
+                .filter(|ds| ds.status() == &status)
+                .collect()
+        });
+        into_future_trait(f)
     }
 
     /// Create a new dataset using full request object.
@@ -601,7 +1236,12 @@ impl Blackfynn {
         &self,
         request: request::dataset::Create,
     ) -> Future<response::Dataset> {
-        post!(self, "/datasets/", params!(), payload!(request))
+        let bf = self.clone();
+        let f = post!(self, "/datasets/", params!(), payload!(request)).then(move |result| {
+            bf.invalidate_dataset(None);
+            result
+        });
+        into_future_trait(f)
     }
 
     /// Create a new dataset with some request parameter defaults.
@@ -615,48 +1255,217 @@ impl Blackfynn {
 
     /// Get a specific dataset by its ID.
     pub fn get_dataset_by_id(&self, id: DatasetNodeId) -> Future<response::Dataset> {
-        get!(self, route!("/datasets/{id}", id))
+        let key = String::from(id.clone());
+        self.cached_get(CachedResource::Dataset, key, route!("/datasets/{id}", id))
     }
 
-    /// Get a specific dataset by its name.
-    pub fn get_dataset_by_name<N: Into<String>>(&self, name: N) -> Future<response::Dataset> {
-        let name = name.into();
-        let inner = self.clone();
-        into_future_trait(self.get_datasets().and_then(move |datasets| {
-            datasets
-                .into_iter()
-                .find(|ds| {
-                    let ds: &model::Dataset = ds.borrow();
-                    ds.name().to_lowercase() == name.to_lowercase()
-                })
-                .ok_or_else(|| Error::invalid_dataset_name(name))
-                .into_future()
-                .and_then(move |ds| {
-                    // NOTE: We must re-request the found dataset, as any dataset
-                    // returned by way of the `/datasets/` route will not include
-                    // child packages:
-                    inner.get_dataset_by_id(ds.id().clone())
-                })
-        }))
-    }
+    /// Get the activity history of a dataset, most recent first, such as
+    /// uploads, moves, renames, deletions and permission changes.
+    ///
+    /// Events are fetched a page at a time, as the stream is consumed.
+    pub fn get_dataset_activity(
+        &self,
+        id: DatasetNodeId,
+        filter: request::activity::ActivityFilter,
+    ) -> Stream<model::ActivityEvent> {
+        let bf = self.clone();
 
-    /// Get a dataset by ID or by name.
-    pub fn get_dataset<N: Into<String>>(&self, id_or_name: N) -> Future<response::Dataset> {
-        let id_or_name = id_or_name.into();
-        let id = DatasetNodeId::from(id_or_name.clone());
-        let name = id_or_name.clone();
+        // `None` once the last page has been fetched, otherwise the cursor
+        // of the next page:
+        let pages = stream::unfold(Some(None), move |cursor: Option<Option<String>>| {
+            let cursor = cursor?;
 
-        // Definitely not a dataset ID - only try to get by name
-        if !id_or_name.starts_with("N:dataset:") {
-            into_future_trait(self.get_dataset_by_name(name))
+            let mut params = filter.params();
+            params.push(param!("limit", ACTIVITY_PAGE_SIZE.to_string()));
+            if let Some(cursor) = cursor {
+                params.push(param!("cursor", cursor));
+            }
 
-        // Even if it looks like an ID it could still be a name - try both methods
-        } else {
-            let inner = self.clone();
-            into_future_trait(
-                self.get_dataset_by_id(id)
-                    .or_else(move |_| inner.get_dataset_by_name(name)),
-            )
+            let id = id.clone();
+            let page = get!(bf, route!("/datasets/{id}/activity", id), params).map(
+                |page: response::ActivityPage| {
+                    let (events, cursor) = page.take();
+                    let next = match cursor {
+                        Some(cursor) if !events.is_empty() => Some(Some(cursor)),
+                        _ => None,
+                    };
+                    (events, next)
+                },
+            );
+            Some(page)
+        });
+
+        into_stream_trait(pages.map(stream::iter_ok).flatten())
+    }
+
+    /// Search the datasets and packages the user has access to. Returns a
+    /// single page of hits, along with the facets of every hit.
+    pub fn search(&self, search: request::search::Search) -> Future<response::SearchResults> {
+        post!(self, "/search", params!(), payload!(search))
+    }
+
+    /// Search the datasets and packages the user has access to, fetching
+    /// every page of hits as the stream is consumed.
+    pub fn search_all(&self, search: request::search::Search) -> Stream<response::SearchHit> {
+        let bf = self.clone();
+
+        let pages = stream::unfold(Some(search), move |search| {
+            let search = search?;
+            let next = search.clone();
+
+            let page = bf.search(search).map(move |results| {
+                let next = results.next_offset().map(|offset| next.with_offset(offset));
+                (results.take(), next)
+            });
+            Some(page)
+        });
+
+        into_stream_trait(pages.map(stream::iter_ok).flatten())
+    }
+
+    /// Fetch the dataset a search hit is, or belongs to.
+    pub fn get_search_hit_dataset(&self, hit: &response::SearchHit) -> Future<response::Dataset> {
+        self.get_dataset_by_id(hit.dataset_id().clone())
+    }
+
+    /// Fetch the package a search hit is.
+    pub fn get_search_hit_package(&self, hit: &response::SearchHit) -> Future<response::Package> {
+        match hit.package_id() {
+            Some(package_id) => self.get_package_by_id(package_id),
+            None => into_future_trait(future::err(Error::invalid_arguments(format!(
+                "search hit is not a package: {}",
+                hit.id()
+            )))),
+        }
+    }
+
+    /// Get the DOI reserved for a dataset.
+    pub fn get_dataset_doi(&self, id: DatasetNodeId) -> Future<response::Doi> {
+        get!(self, route!("/datasets/{id}/doi", id))
+    }
+
+    /// Reserve a DOI for a dataset, so it can be cited before the dataset
+    /// is published. The DOI becomes findable once the dataset is
+    /// published.
+    pub fn reserve_dataset_doi(&self, id: DatasetNodeId) -> Future<response::Doi> {
+        post!(self, route!("/datasets/{id}/doi", id))
+    }
+
+    /// Get the state of the latest publication request of a dataset.
+    pub fn get_publication_status(&self, id: DatasetNodeId) -> Future<response::Publication> {
+        get!(self, route!("/datasets/{id}/publication", id))
+    }
+
+    /// Request the publication of a dataset.
+    ///
+    /// The dataset is checked before the request is sent: if it cannot be
+    /// published, or the platform refuses the request listing what blocks
+    /// it, the error is an `ErrorKind::PublicationBlocked` listing every
+    /// rule that was broken. An embargo may be released today at the
+    /// earliest.
+    pub fn request_publication(
+        &self,
+        id: DatasetNodeId,
+        publication: request::dataset::Publication,
+    ) -> Future<response::Publication> {
+        if publication
+            .embargo_release_date()
+            .is_some_and(|date| *date < chrono::Utc::now().date_naive())
+        {
+            return into_future_trait(future::err(Error::publication_blocked(vec![
+                model::PublicationRule::EmbargoDateInPast,
+            ])));
+        }
+
+        let bf = self.clone();
+        let f = self
+            .get_dataset_by_id(id.clone())
+            .join(self.get_dataset_readme(id.clone()))
+            .and_then(|(dataset, readme)| {
+                let blockers = model::publication_blockers(&dataset, &readme);
+                if blockers.is_empty() {
+                    Ok(())
+                } else {
+                    Err(Error::publication_blocked(blockers))
+                }
+            })
+            .and_then(move |_| {
+                let dataset_id = id.clone();
+                post!(
+                    bf,
+                    route!("/datasets/{id}/publication/request", id),
+                    params!(),
+                    payload!(publication)
+                )
+                .map_err(|err| {
+                    let blockers = match err.kind() {
+                        ErrorKind::ApiError {
+                            status_code: StatusCode::BAD_REQUEST,
+                            message,
+                        } => model::rejection_blockers(message),
+                        _ => None,
+                    };
+                    blockers.map_or(err, Error::publication_blocked)
+                })
+                .then(move |result| {
+                    bf.invalidate_dataset(Some(&dataset_id));
+                    result
+                })
+            });
+
+        into_future_trait(f)
+    }
+
+    /// Withdraw a pending publication request of a dataset.
+    pub fn withdraw_publication_request(&self, id: DatasetNodeId) -> Future<response::Publication> {
+        let bf = self.clone();
+        let dataset_id = id.clone();
+        let f = post!(self, route!("/datasets/{id}/publication/cancel", id)).then(move |result| {
+            bf.invalidate_dataset(Some(&dataset_id));
+            result
+        });
+        into_future_trait(f)
+    }
+
+    /// Get a specific dataset by its name.
+    pub fn get_dataset_by_name<N: Into<String>>(&self, name: N) -> Future<response::Dataset> {
+        let name = name.into();
+        let inner = self.clone();
+        into_future_trait(self.get_datasets().and_then(move |datasets| {
+            datasets
+                .into_iter()
+                .find(|ds| {
+                    let ds: &model::Dataset = ds.borrow();
+                    ds.name().to_lowercase() == name.to_lowercase()
+                })
+                .ok_or_else(|| Error::invalid_dataset_name(name))
+                .into_future()
+                .and_then(move |ds| {
+                    // NOTE: We must re-request the found dataset, as any dataset
+                    // returned by way of the `/datasets/` route will not include
+                    // child packages:
+                    inner.get_dataset_by_id(ds.id().clone())
+                })
+        }))
+    }
+
+    /// Get a dataset by ID or by name.
+    pub fn get_dataset<N: Into<String>>(&self, id_or_name: N) -> Future<response::Dataset> {
+        let id_or_name = id_or_name.into();
+        let id = DatasetNodeId::from(id_or_name.clone());
+        let name = id_or_name.clone();
+
+        // Definitely not a dataset ID - only try to get by name
+        if !id_or_name.starts_with("N:dataset:") {
+            into_future_trait(self.get_dataset_by_name(name))
+
+        // Even if it looks like an ID it could still be a name - try both methods
+        } else {
+            let inner = self.clone();
+            into_future_trait(
+                self.get_dataset_by_id(id)
+                    .or_else(move |_| inner.get_dataset_by_name(name)),
+            )
         }
     }
 
@@ -681,132 +1490,2060 @@ impl Blackfynn {
         )
     }
 
-    /// Update an existing dataset.
-    pub fn update_dataset<N: Into<String>, D: Into<String>>(
+    /// Get the role the current user effectively has on the data set: the
+    /// highest of the roles granted to the user directly, through any of
+    /// their teams, and through the organization.
+    ///
+    /// Returns `None` if the user has not been granted any role. A data set
+    /// that is not shared with its organization grants no organization role.
+    pub fn my_dataset_role(&self, id: DatasetNodeId) -> Future<Option<Role>> {
+        let bf = self.clone();
+
+        // A data set not shared with the organization has no organization
+        // role:
+        let organization_role = self
+            .get_dataset_organization_role(id.clone())
+            .map(Some)
+            .or_else(|err| match err.kind() {
+                ErrorKind::ApiError {
+                    status_code: StatusCode::NOT_FOUND,
+                    ..
+                } => Ok(None),
+                _ => Err(err),
+            });
+
+        let f = self
+            .get_user()
+            .join5(
+                self.get_dataset_by_id(id.clone()),
+                self.get_dataset_user_collaborators(id.clone()),
+                self.get_dataset_team_collaborators(id),
+                organization_role,
+            )
+            .and_then(move |(user, dataset, users, teams, organization)| {
+                let mut roles: Vec<Role> = users
+                    .iter()
+                    .filter(|u| u.id() == user.id())
+                    .filter_map(|u| u.role().cloned())
+                    .collect();
+                if &UserId::new(dataset.owner().clone()) == user.id() {
+                    roles.push(Role::Owner);
+                }
+                roles.extend(organization.and_then(|o| o.role().cloned()));
+
+                // Only the members of teams that could raise the role are
+                // fetched:
+                let best = roles.iter().max().cloned();
+                let organization_id = OrganizationId::new(dataset.organization().clone());
+                let team_roles: Vec<_> = teams
+                    .into_iter()
+                    .filter_map(|team| team.role().cloned().map(|role| (team, role)))
+                    .filter(|(_, role)| best.as_ref().is_none_or(|best| role > best))
+                    .map(|(team, role)| {
+                        let user_id = user.id().clone();
+                        bf.get_team_members(organization_id.clone(), team.id().clone())
+                            .map(move |members| {
+                                if members.iter().any(|member| member.id() == &user_id) {
+                                    Some(role)
+                                } else {
+                                    None
+                                }
+                            })
+                    })
+                    .collect();
+
+                future::join_all(team_roles).map(move |team_roles| {
+                    roles
+                        .into_iter()
+                        .chain(team_roles.into_iter().flatten())
+                        .max()
+                })
+            });
+
+        into_future_trait(f)
+    }
+
+    /// Share the data set with several users, granting each of them `role`.
+    ///
+    /// Ownership cannot be shared; see `transfer_dataset_ownership`.
+    pub fn add_dataset_user_collaborators(
         &self,
         id: DatasetNodeId,
-        name: N,
-        description: Option<D>,
-    ) -> Future<response::Dataset> {
-        put!(
+        users: Vec<UserId>,
+        role: Role,
+    ) -> Future<response::CollaboratorChanges> {
+        if role == Role::Owner {
+            return into_future_trait(future::err(Error::invalid_arguments(
+                "use transfer_dataset_ownership to change the owner of a data set",
+            )));
+        }
+        let bf = self.clone();
+        let dataset_id = id.clone();
+        let f = put!(
             self,
-            route!("/datasets/{id}", id),
+            route!("/datasets/{id}/collaborators/users", id),
             params!(),
-            payload!(request::dataset::Update::new(name, description))
+            payload!(request::collaborator::Share::new(users, role))
         )
+        .then(move |result| {
+            bf.invalidate_dataset(Some(&dataset_id));
+            result
+        });
+        into_future_trait(f)
     }
 
-    /// Delete an existing dataset.
-    pub fn delete_dataset(&self, id: DatasetNodeId) -> Future<()> {
-        let f: Future<response::EmptyMap> = delete!(self, route!("/datasets/{id}", id));
-        into_future_trait(f.map(|_| ()))
-    }
-
-    /// Create a new package.
-    /// TODO: see https://github.com/Blackfynn/blackfynn-rust/pull/45/files#r265581502
-    /// for a strategy for cleaning up API functions with many optional arguments.
-    pub fn create_package<N, D, P, F>(
+    /// Stop sharing the data set with several users.
+    pub fn remove_dataset_user_collaborators(
         &self,
-        name: N,
-        package_type: P,
-        dataset: D,
-        parent: Option<F>,
-    ) -> Future<response::Package>
-    where
-        D: Into<DatasetNodeId>,
-        N: Into<String>,
-        P: Into<String>,
-        F: Into<String>,
-    {
-        post!(
+        id: DatasetNodeId,
+        users: Vec<UserId>,
+    ) -> Future<response::CollaboratorChanges> {
+        let bf = self.clone();
+        let dataset_id = id.clone();
+        let f = delete!(
             self,
-            "/packages/",
+            route!("/datasets/{id}/collaborators/users", id),
             params!(),
-            payload!(request::package::Create::new(
-                name,
-                package_type,
-                dataset,
-                parent
-            ))
+            payload!(request::collaborator::Unshare::new(users))
         )
+        .then(move |result| {
+            bf.invalidate_dataset(Some(&dataset_id));
+            result
+        });
+        into_future_trait(f)
     }
 
-    /// Get a specific package.
-    pub fn get_package_by_id(&self, id: PackageId) -> Future<response::Package> {
-        get!(self, route!("/packages/{id}", id))
+    /// Share the data set with several teams, granting each of them `role`.
+    ///
+    /// Ownership cannot be shared; see `transfer_dataset_ownership`.
+    pub fn add_dataset_team_collaborators(
+        &self,
+        id: DatasetNodeId,
+        teams: Vec<TeamId>,
+        role: Role,
+    ) -> Future<response::CollaboratorChanges> {
+        if role == Role::Owner {
+            return into_future_trait(future::err(Error::invalid_arguments(
+                "use transfer_dataset_ownership to change the owner of a data set",
+            )));
+        }
+        let bf = self.clone();
+        let dataset_id = id.clone();
+        let f = put!(
+            self,
+            route!("/datasets/{id}/collaborators/teams", id),
+            params!(),
+            payload!(request::collaborator::Share::new(teams, role))
+        )
+        .then(move |result| {
+            bf.invalidate_dataset(Some(&dataset_id));
+            result
+        });
+        into_future_trait(f)
     }
 
-    /// Get the source files that are part of a package.
-    pub fn get_package_sources(&self, id: PackageId) -> Future<response::Files> {
-        get!(self, route!("/packages/{id}/sources", id))
+    /// Stop sharing the data set with several teams.
+    pub fn remove_dataset_team_collaborators(
+        &self,
+        id: DatasetNodeId,
+        teams: Vec<TeamId>,
+    ) -> Future<response::CollaboratorChanges> {
+        let bf = self.clone();
+        let dataset_id = id.clone();
+        let f = delete!(
+            self,
+            route!("/datasets/{id}/collaborators/teams", id),
+            params!(),
+            payload!(request::collaborator::Unshare::new(teams))
+        )
+        .then(move |result| {
+            bf.invalidate_dataset(Some(&dataset_id));
+            result
+        });
+        into_future_trait(f)
     }
 
-    /// Update an existing package.
-    pub fn update_package<N: Into<String>>(
+    /// Share the data set with every member of the organization, granting
+    /// each of them `role`.
+    pub fn set_dataset_organization_role(
         &self,
-        id: PackageId,
-        name: N,
-    ) -> Future<response::Package> {
-        put!(
+        id: DatasetNodeId,
+        role: Role,
+    ) -> Future<response::OrganizationRole> {
+        if role == Role::Owner {
+            return into_future_trait(future::err(Error::invalid_arguments(
+                "use transfer_dataset_ownership to change the owner of a data set",
+            )));
+        }
+        let bf = self.clone();
+        let dataset_id = id.clone();
+        let f = put!(
             self,
-            route!("/packages/{id}", id),
+            route!("/datasets/{id}/collaborators/organizations", id),
             params!(),
-            payload!(request::package::Update::new(name))
+            payload!(request::collaborator::OrganizationRole::new(role))
         )
+        .then(move |result| {
+            bf.invalidate_dataset(Some(&dataset_id));
+            result
+        });
+        into_future_trait(f)
     }
 
-    /// Process a package in the UPLOADED state.
-    pub fn process_package(&self, id: PackageId) -> Future<()> {
-        let f = put!(self, route!("/packages/{id}/process", id)).map(|_: Nothing| ());
+    /// Stop sharing the data set with the members of the organization.
+    pub fn remove_dataset_organization_role(&self, id: DatasetNodeId) -> Future<()> {
+        let bf = self.clone();
+        let dataset_id = id.clone();
+        let f = delete!(
+            self,
+            route!("/datasets/{id}/collaborators/organizations", id)
+        )
+        .map(|_: Nothing| ())
+        .then(move |result| {
+            bf.invalidate_dataset(Some(&dataset_id));
+            result
+        });
         into_future_trait(f)
     }
 
-    /// Move several packages to a destination package.
-    /// If destination is None, the package is moved to the top level of the dataset.
-    pub fn mv<T: Into<PackageId>, D: Into<PackageId>>(
-        &self,
-        things: Vec<T>,
-        destination: Option<D>,
-    ) -> Future<response::MoveResponse> {
-        post!(
+    /// Make another user the owner of the data set.
+    pub fn transfer_dataset_ownership(&self, id: DatasetNodeId, user: UserId) -> Future<()> {
+        let bf = self.clone();
+        let dataset_id = id.clone();
+        let f = put!(
             self,
-            "/data/move",
+            route!("/datasets/{id}/collaborators/owner", id),
             params!(),
-            payload!(request::mv::Move::new(things, destination))
+            payload!(request::collaborator::TransferOwnership::new(user))
         )
+        .map(|_: Nothing| ())
+        .then(move |result| {
+            bf.invalidate_dataset(Some(&dataset_id));
+            result
+        });
+        into_future_trait(f)
     }
 
-    /// Get the members that belong to the current users organization.
-    pub fn get_members(&self) -> Future<Vec<model::User>> {
-        into_future_trait(match self.current_organization() {
-            Some(org) => self.get_members_by_organization(org),
-            None => into_future_trait(future::err::<_, Error>(ErrorKind::NoOrganizationSet.into())),
-        })
+    /// Update the name, and optionally the description, of an existing
+    /// dataset.
+    pub fn update_dataset<N: Into<String>, D: Into<String>>(
+        &self,
+        id: DatasetNodeId,
+        name: N,
+        description: Option<D>,
+    ) -> Future<response::Dataset> {
+        self.update_dataset_with_request(id, request::dataset::Update::new(name, description))
     }
 
-    /// Get the members that belong to the specified organization.
-    pub fn get_members_by_organization(&self, id: OrganizationId) -> Future<Vec<model::User>> {
-        get!(self, route!("/organizations/{id}/members", id))
+    /// Update an existing dataset using a full request object. Fields not
+    /// set in the request are left as they are.
+    pub fn update_dataset_with_request(
+        &self,
+        id: DatasetNodeId,
+        request: request::dataset::Update,
+    ) -> Future<response::Dataset> {
+        let bf = self.clone();
+        let dataset_id = id.clone();
+        let f = put!(
+            self,
+            route!("/datasets/{id}", id),
+            params!(),
+            payload!(request)
+        )
+        .then(move |result| {
+            bf.invalidate_dataset(Some(&dataset_id));
+            result
+        });
+        into_future_trait(f)
     }
 
-    /// Get the members that belong to the current users organization.
-    pub fn get_teams(&self) -> Future<Vec<response::Team>> {
-        into_future_trait(match self.current_organization() {
-            Some(org) => self.get_teams_by_organization(org),
-            None => into_future_trait(future::err::<_, Error>(ErrorKind::NoOrganizationSet.into())),
-        })
+    /// Get a specific dataset, along with its `ETag` to pass to
+    /// `update_dataset_if_match`.
+    pub fn get_dataset_with_etag(
+        &self,
+        id: DatasetNodeId,
+    ) -> Future<(response::Dataset, Option<String>)> {
+        self.get_with_etag(route!("/datasets/{id}", id))
     }
 
-    /// Get the teams that belong to the specified organization.
-    pub fn get_teams_by_organization(&self, id: OrganizationId) -> Future<Vec<response::Team>> {
-        get!(self, route!("/organizations/{id}/teams", id))
+    /// Update an existing dataset, as `update_dataset_with_request` does,
+    /// only if it did not change since it had the given `ETag`. Fails with
+    /// `ErrorKind::Conflict` otherwise.
+    pub fn update_dataset_if_match<E: Into<String>>(
+        &self,
+        id: DatasetNodeId,
+        request: request::dataset::Update,
+        etag: E,
+    ) -> Future<response::Dataset> {
+        let bf = self.clone();
+        let dataset_id = id.clone();
+        let f = self
+            .conditional_put(route!("/datasets/{id}", id), &request, etag.into())
+            .then(move |result| {
+                bf.invalidate_dataset(Some(&dataset_id));
+                result
+            });
+        into_future_trait(f)
     }
 
-    /// Generate a preview of the files to be uploaded.
-    pub fn preview_upload<P, Q>(
-        &self,
-        organization_id: &OrganizationId,
-        dataset_id: &DatasetId,
+    /// Get the markdown README of a dataset.
+    pub fn get_dataset_readme(&self, id: DatasetNodeId) -> Future<String> {
+        let f = get!(self, route!("/datasets/{id}/readme", id)).map(response::Readme::take);
+        into_future_trait(f)
+    }
+
+    /// Replace the markdown README of a dataset.
+    pub fn set_dataset_readme<R: Into<String>>(&self, id: DatasetNodeId, readme: R) -> Future<()> {
+        let bf = self.clone();
+        let dataset_id = id.clone();
+        let f = put!(
+            self,
+            route!("/datasets/{id}/readme", id),
+            params!(),
+            payload!(request::dataset::Readme::new(readme))
+        )
+        .map(|_: Nothing| ())
+        .then(move |result| {
+            bf.invalidate_dataset(Some(&dataset_id));
+            result
+        });
+        into_future_trait(f)
+    }
+
+    /// Get a temporary URL of the banner image of a dataset, if it has one.
+    pub fn get_dataset_banner_url(&self, id: DatasetNodeId) -> Future<Option<String>> {
+        let f = get!(self, route!("/datasets/{id}/banner", id)).map(response::Banner::take);
+        into_future_trait(f)
+    }
+
+    /// Download the banner image of a dataset, if it has one.
+    pub fn download_dataset_banner(&self, id: DatasetNodeId) -> Future<Option<Vec<u8>>> {
+        self.download_dataset_banner_with_cancellation(id, CancellationToken::new())
+    }
+
+    /// Like `download_dataset_banner`, failing with `ErrorKind::Cancelled`
+    /// once `cancellation` is cancelled.
+    pub fn download_dataset_banner_with_cancellation(
+        &self,
+        id: DatasetNodeId,
+        cancellation: CancellationToken,
+    ) -> Future<Option<Vec<u8>>> {
+        let bf = self.clone();
+        let f = self
+            .get_dataset_banner_url(id)
+            .and_then(move |url| match url {
+                Some(url) => into_future_trait(bf.fetch_url(url, cancellation).map(Some)),
+                None => into_future_trait(future::ok(None)),
+            });
+        into_future_trait(f)
+    }
+
+    /// Get the dataset statuses configured by an organization.
+    pub fn get_dataset_statuses(
+        &self,
+        organization_id: OrganizationId,
+    ) -> Future<Vec<model::DatasetStatusOption>> {
+        get!(
+            self,
+            route!(
+                "/organizations/{organization_id}/dataset-status",
+                organization_id
+            )
+        )
+    }
+
+    /// Set the status of a dataset. If the client has a current
+    /// organization, the status must be one the organization configured.
+    pub fn set_dataset_status(
+        &self,
+        id: DatasetNodeId,
+        status: DatasetStatus,
+    ) -> Future<response::Dataset> {
+        let bf = self.clone();
+        let wanted = status.clone();
+        let update = move |_| {
+            bf.update_dataset_with_request(
+                id,
+                request::dataset::Update::default().with_status(status),
+            )
+        };
+
+        match self.current_organization() {
+            Some(organization_id) => {
+                let f = self
+                    .get_dataset_statuses(organization_id)
+                    .and_then(move |statuses| {
+                        if statuses.iter().any(|s| s.status() == &wanted) {
+                            Ok(())
+                        } else {
+                            Err(Error::invalid_arguments(format!(
+                                "no such dataset status: {}",
+                                wanted
+                            )))
+                        }
+                    })
+                    .and_then(update);
+                into_future_trait(f)
+            }
+            None => update(()),
+        }
+    }
+
+    /// Replace the banner image of a dataset with a PNG or JPEG file.
+    /// Returns a temporary URL of the new banner.
+    pub fn upload_dataset_banner<P: AsRef<Path>>(
+        &self,
+        id: DatasetNodeId,
+        path: P,
+    ) -> Future<Option<String>> {
+        let path = path.as_ref();
+        let content_type = match path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(|ext| ext.to_lowercase())
+        {
+            Some(ref ext) if ext == "png" => "image/png",
+            Some(ref ext) if ext == "jpg" || ext == "jpeg" => "image/jpeg",
+            _ => {
+                return into_future_trait(future::err(Error::invalid_arguments(format!(
+                    "banners must be PNG or JPEG images: {:?}",
+                    path
+                ))))
+            }
+        };
+
+        let body = match fs::read(path) {
+            Ok(body) => body,
+            Err(err) => return into_future_trait(future::err(err.into())),
+        };
+
+        let bf = self.clone();
+        let dataset_id = id.clone();
+        let f = self
+            .request_with_body(
+                route!("/datasets/{id}/banner", id),
+                Method::PUT,
+                params!(),
+                body,
+                vec![(
+                    hyper::header::CONTENT_TYPE,
+                    HeaderValue::from_static(content_type),
+                )],
+                false,
+            )
+            .map(response::Banner::take)
+            .then(move |result| {
+                bf.invalidate_dataset(Some(&dataset_id));
+                result
+            });
+        into_future_trait(f)
+    }
+
+    /// Delete an existing dataset.
+    pub fn delete_dataset(&self, id: DatasetNodeId) -> Future<()> {
+        let bf = self.clone();
+        let dataset_id = id.clone();
+        let f: Future<response::EmptyMap> = delete!(self, route!("/datasets/{id}", id));
+        into_future_trait(f.map(|_| ()).then(move |result| {
+            bf.invalidate_dataset(Some(&dataset_id));
+            result
+        }))
+    }
+
+    /// Create a new package.
+    /// TODO: see https://github.com/Blackfynn/blackfynn-rust/pull/45/files#r265581502
+    /// for a strategy for cleaning up API functions with many optional arguments.
+    pub fn create_package<N, D, P, F>(
+        &self,
+        name: N,
+        package_type: P,
+        dataset: D,
+        parent: Option<F>,
+    ) -> Future<response::Package>
+    where
+        D: Into<DatasetNodeId>,
+        N: Into<String>,
+        P: Into<String>,
+        F: Into<String>,
+    {
+        let bf = self.clone();
+        let dataset_id = dataset.into();
+        let f = post!(
+            self,
+            "/packages/",
+            params!(),
+            payload!(request::package::Create::new(
+                name,
+                package_type,
+                dataset_id.clone(),
+                parent
+            ))
+        )
+        .then(move |result| {
+            bf.invalidate_dataset(Some(&dataset_id));
+            result
+        });
+        into_future_trait(f)
+    }
+
+    /// Get a specific package.
+    pub fn get_package_by_id(&self, id: PackageId) -> Future<response::Package> {
+        get!(self, route!("/packages/{id}", id))
+    }
+
+    /// Get a specific package, along with its `ETag` to pass to
+    /// `update_package_if_match`.
+    pub fn get_package_with_etag(
+        &self,
+        id: PackageId,
+    ) -> Future<(response::Package, Option<String>)> {
+        self.get_with_etag(route!("/packages/{id}", id))
+    }
+
+    /// Get the source files that are part of a package.
+    pub fn get_package_sources(&self, id: PackageId) -> Future<response::Files> {
+        get!(self, route!("/packages/{id}/sources", id))
+    }
+
+    /// Update an existing package.
+    pub fn update_package<N: Into<String>>(
+        &self,
+        id: PackageId,
+        name: N,
+    ) -> Future<response::Package> {
+        // Cached datasets include their packages:
+        let bf = self.clone();
+        let f = put!(
+            self,
+            route!("/packages/{id}", id),
+            params!(),
+            payload!(request::package::Update::new(name))
+        )
+        .then(move |result| {
+            bf.invalidate_dataset(None);
+            result
+        });
+        into_future_trait(f)
+    }
+
+    /// Update an existing package, as `update_package` does, only if it
+    /// did not change since it had the given `ETag`. Fails with
+    /// `ErrorKind::Conflict` otherwise.
+    pub fn update_package_if_match<N, E>(
+        &self,
+        id: PackageId,
+        name: N,
+        etag: E,
+    ) -> Future<response::Package>
+    where
+        N: Into<String>,
+        E: Into<String>,
+    {
+        // Cached datasets include their packages:
+        let bf = self.clone();
+        let f = self
+            .conditional_put(
+                route!("/packages/{id}", id),
+                &request::package::Update::new(name),
+                etag.into(),
+            )
+            .then(move |result| {
+                bf.invalidate_dataset(None);
+                result
+            });
+        into_future_trait(f)
+    }
+
+    /// Process a package in the UPLOADED state.
+    pub fn process_package(&self, id: PackageId) -> Future<()> {
+        let f = put!(self, route!("/packages/{id}/process", id)).map(|_: Nothing| ());
+        into_future_trait(f)
+    }
+
+    /// Get the channels of a timeseries package.
+    pub fn get_channels(&self, id: PackageId) -> Future<Vec<model::Channel>> {
+        let f = get!(self, route!("/timeseries/{id}/channels", id)).map(
+            |channels: Vec<response::Channel>| {
+                channels.into_iter().map(response::Channel::take).collect()
+            },
+        );
+        into_future_trait(f)
+    }
+
+    /// Create a channel on a timeseries package.
+    pub fn create_channel(
+        &self,
+        id: PackageId,
+        channel: request::channel::Channel,
+    ) -> Future<model::Channel> {
+        let f = post!(
+            self,
+            route!("/timeseries/{id}/channels", id),
+            params!(),
+            payload!(channel)
+        )
+        .map(response::Channel::take);
+        into_future_trait(f)
+    }
+
+    /// Get temporary credentials to stream timeseries data with.
+    pub fn get_streaming_credentials(&self) -> Future<model::TemporaryCredential> {
+        let f = get!(self, "/security/user/credentials/streaming")
+            .map(response::TemporaryCredential::take);
+        into_future_trait(f)
+    }
+
+    /// Send a single block of samples to a timeseries package. Blocks are
+    /// identified by their sequence number, so sending a block twice has
+    /// no further effect.
+    pub fn send_timeseries_block(
+        &self,
+        id: PackageId,
+        credential: &model::TemporaryCredential,
+        block: &model::StreamBlock,
+    ) -> Future<()> {
+        let f = post!(
+            self,
+            route!("/streaming/timeseries/{id}/blocks", id),
+            params!("sessionToken" => credential.session_token()),
+            payload!(block)
+        )
+        .map(|_: Nothing| ());
+        into_future_trait(f)
+    }
+
+    /// Open a writer streaming samples to the channels of a timeseries
+    /// package, spooling blocks in `spool_dir` until they are sent. Blocks
+    /// left in the spool by a previous writer of the package are sent by
+    /// the next flush. Fails if the spool holds unsent blocks of another
+    /// package.
+    pub fn timeseries_writer<P: AsRef<Path>>(
+        &self,
+        id: PackageId,
+        spool_dir: P,
+        options: StreamingOptions,
+    ) -> Result<TimeseriesWriter> {
+        TimeseriesWriter::open(self.clone(), id, spool_dir, options)
+    }
+
+    /// Get the samples of several channels of a timeseries package within
+    /// the time range `[start, end)`, in microseconds. If `channels` is
+    /// empty, the samples of every channel are fetched.
+    ///
+    /// The range is fetched in pages, one after the other, so long
+    /// recordings can be processed without holding every sample in
+    /// memory. Each page yields one block per channel.
+    pub fn get_timeseries(
+        &self,
+        id: PackageId,
+        channels: Vec<ChannelId>,
+        start: i64,
+        end: i64,
+    ) -> Stream<model::SampleBlock> {
+        self.get_timeseries_with_page_duration(
+            id,
+            channels,
+            start,
+            end,
+            DEFAULT_TIMESERIES_PAGE_MICROS,
+        )
+    }
+
+    /// Like `get_timeseries`, fetching pages lasting at most
+    /// `page_duration` microseconds.
+    pub fn get_timeseries_with_page_duration(
+        &self,
+        id: PackageId,
+        channels: Vec<ChannelId>,
+        start: i64,
+        end: i64,
+        page_duration: i64,
+    ) -> Stream<model::SampleBlock> {
+        self.get_timeseries_with_cancellation(
+            id,
+            channels,
+            start,
+            end,
+            page_duration,
+            CancellationToken::new(),
+        )
+    }
+
+    /// Like `get_timeseries_with_page_duration`. Once `cancellation` is
+    /// cancelled, no further pages are requested and the stream ends with
+    /// `ErrorKind::Cancelled`.
+    pub fn get_timeseries_with_cancellation(
+        &self,
+        id: PackageId,
+        channels: Vec<ChannelId>,
+        start: i64,
+        end: i64,
+        page_duration: i64,
+        cancellation: CancellationToken,
+    ) -> Stream<model::SampleBlock> {
+        if start > end {
+            return into_stream_trait(stream::once(Err(Error::invalid_arguments(format!(
+                "the start of a time range ({}) must not be after its end ({})",
+                start, end
+            )))));
+        }
+
+        if page_duration <= 0 {
+            return into_stream_trait(stream::once(Err(Error::invalid_arguments(format!(
+                "the duration of a page ({}) must be positive",
+                page_duration
+            )))));
+        }
+
+        let bf = self.clone();
+
+        // Pages are requested one at a time, starting from the end of the
+        // previous page:
+        let pages = stream::unfold(start, move |page_start| {
+            let (page_start, page_end) = next_timeseries_page(page_start, end, page_duration)?;
+            let page = match cancellation.check() {
+                Ok(()) => bf.get_timeseries_page(id.clone(), &channels, page_start, page_end),
+                Err(err) => into_future_trait(future::err(err)),
+            };
+            Some(page.map(move |blocks| (blocks, page_end)))
+        });
+
+        into_stream_trait(pages.map(stream::iter_ok).flatten())
+    }
+
+    /// Get a single page of samples of a timeseries package.
+    fn get_timeseries_page(
+        &self,
+        id: PackageId,
+        channels: &[ChannelId],
+        start: i64,
+        end: i64,
+    ) -> Future<Vec<model::SampleBlock>> {
+        let mut params = params!(
+            "start" => start.to_string(),
+            "end" => end.to_string()
+        );
+        if !channels.is_empty() {
+            let channels: Vec<&str> = channels.iter().map(|c| c.as_str()).collect();
+            params.push(param!("channelIds", channels.join(",")));
+        }
+
+        get!(self, route!("/timeseries/{id}/data", id), params)
+    }
+
+    /// Get the annotation layers of a timeseries package.
+    pub fn get_annotation_layers(&self, id: PackageId) -> Future<Vec<model::AnnotationLayer>> {
+        get!(self, route!("/timeseries/{id}/layers", id))
+    }
+
+    /// Create an annotation layer on a timeseries package.
+    pub fn create_annotation_layer(
+        &self,
+        id: PackageId,
+        layer: request::annotation::AnnotationLayer,
+    ) -> Future<model::AnnotationLayer> {
+        post!(
+            self,
+            route!("/timeseries/{id}/layers", id),
+            params!(),
+            payload!(layer)
+        )
+    }
+
+    /// Delete an annotation layer, and every annotation within it.
+    pub fn delete_annotation_layer(
+        &self,
+        id: PackageId,
+        layer_id: AnnotationLayerId,
+    ) -> Future<()> {
+        let f = delete!(
+            self,
+            route!("/timeseries/{id}/layers/{layer_id}", id, layer_id)
+        )
+        .map(|_: Nothing| ());
+        into_future_trait(f)
+    }
+
+    /// Get the annotations within a layer.
+    pub fn get_annotations(
+        &self,
+        id: PackageId,
+        layer_id: AnnotationLayerId,
+    ) -> Future<Vec<model::Annotation>> {
+        get!(
+            self,
+            route!(
+                "/timeseries/{id}/layers/{layer_id}/annotations",
+                id,
+                layer_id
+            )
+        )
+    }
+
+    /// Add an annotation to a layer.
+    pub fn create_annotation(
+        &self,
+        id: PackageId,
+        layer_id: AnnotationLayerId,
+        annotation: request::annotation::Annotation,
+    ) -> Future<model::Annotation> {
+        post!(
+            self,
+            route!(
+                "/timeseries/{id}/layers/{layer_id}/annotations",
+                id,
+                layer_id
+            ),
+            params!(),
+            payload!(annotation)
+        )
+    }
+
+    /// Replace the channels, time range, label and description of an
+    /// annotation.
+    pub fn update_annotation(
+        &self,
+        id: PackageId,
+        layer_id: AnnotationLayerId,
+        annotation_id: AnnotationId,
+        annotation: request::annotation::Annotation,
+    ) -> Future<model::Annotation> {
+        put!(
+            self,
+            route!(
+                "/timeseries/{id}/layers/{layer_id}/annotations/{annotation_id}",
+                id,
+                layer_id,
+                annotation_id
+            ),
+            params!(),
+            payload!(annotation)
+        )
+    }
+
+    /// Delete an annotation.
+    pub fn delete_annotation(
+        &self,
+        id: PackageId,
+        layer_id: AnnotationLayerId,
+        annotation_id: AnnotationId,
+    ) -> Future<()> {
+        let f = delete!(
+            self,
+            route!(
+                "/timeseries/{id}/layers/{layer_id}/annotations/{annotation_id}",
+                id,
+                layer_id,
+                annotation_id
+            )
+        )
+        .map(|_: Nothing| ());
+        into_future_trait(f)
+    }
+
+    /// Add several annotations to a layer, one after the other.
+    ///
+    /// Every annotation is checked against the channels of the package
+    /// before any is created: each must name at least one channel of the
+    /// package, and fall within the recording of every channel it names.
+    ///
+    /// If creating an annotation fails, the import stops with
+    /// `ErrorKind::AnnotationImportFailed`, which counts the annotations
+    /// created before it. Those are the first annotations given, so the
+    /// import can be resumed from the one that failed.
+    pub fn import_annotations(
+        &self,
+        id: PackageId,
+        layer_id: AnnotationLayerId,
+        annotations: Vec<request::annotation::Annotation>,
+    ) -> Future<Vec<model::Annotation>> {
+        let bf = self.clone();
+
+        let f = self
+            .get_channels(id.clone())
+            .and_then(move |channels| {
+                let channels: HashMap<ChannelId, model::Channel> =
+                    channels.into_iter().map(|c| (c.id().clone(), c)).collect();
+
+                for (i, annotation) in annotations.iter().enumerate() {
+                    if annotation.channel_ids().is_empty() {
+                        return Err(Error::invalid_arguments(format!(
+                            "annotation {} (\"{}\") names no channels",
+                            i + 1,
+                            annotation.label()
+                        )));
+                    }
+                    for channel_id in annotation.channel_ids() {
+                        match channels.get(channel_id) {
+                            None => {
+                                return Err(Error::invalid_arguments(format!(
+                                    "annotation {} (\"{}\"): no such channel: {}",
+                                    i + 1,
+                                    annotation.label(),
+                                    channel_id
+                                )))
+                            }
+                            Some(channel)
+                                if annotation.start() < channel.start()
+                                    || annotation.end() > channel.end() =>
+                            {
+                                return Err(Error::invalid_arguments(format!(
+                                    "annotation {} (\"{}\") falls outside of channel {}",
+                                    i + 1,
+                                    annotation.label(),
+                                    channel_id
+                                )))
+                            }
+                            Some(_) => {}
+                        }
+                    }
+                }
+
+                Ok(annotations)
+            })
+            .and_then(move |annotations| {
+                stream::iter_ok(annotations).fold(vec![], move |mut created, annotation| {
+                    bf.create_annotation(id.clone(), layer_id, annotation)
+                        .then(move |result| match result {
+                            Ok(annotation) => {
+                                created.push(annotation);
+                                Ok(created)
+                            }
+                            Err(err) => Err(Error::annotation_import_failed(created.len(), &err)),
+                        })
+                })
+            });
+
+        into_future_trait(f)
+    }
+
+    /// Add the annotations listed in a CSV file to a layer. See
+    /// `request::annotation::Annotation::from_csv` for the expected format.
+    pub fn import_annotations_csv<P: AsRef<Path>>(
+        &self,
+        id: PackageId,
+        layer_id: AnnotationLayerId,
+        path: P,
+    ) -> Future<Vec<model::Annotation>> {
+        let annotations = fs::File::open(path.as_ref())
+            .map_err(Into::into)
+            .and_then(request::annotation::Annotation::from_csv);
+
+        match annotations {
+            Ok(annotations) => self.import_annotations(id, layer_id, annotations),
+            Err(err) => into_future_trait(future::err(err)),
+        }
+    }
+
+    /// Get the metadata concepts (models) of a dataset.
+    pub fn get_concepts(&self, dataset_id: DatasetNodeId) -> Future<Vec<model::Concept>> {
+        get!(
+            self,
+            route!("/models/datasets/{dataset_id}/concepts", dataset_id)
+        )
+    }
+
+    /// Create a metadata concept in a dataset.
+    pub fn create_concept(
+        &self,
+        dataset_id: DatasetNodeId,
+        concept: request::concept::Concept,
+    ) -> Future<model::Concept> {
+        post!(
+            self,
+            route!("/models/datasets/{dataset_id}/concepts", dataset_id),
+            params!(),
+            payload!(concept)
+        )
+    }
+
+    /// Update the name and description of a metadata concept.
+    pub fn update_concept(
+        &self,
+        dataset_id: DatasetNodeId,
+        concept_id: ConceptId,
+        concept: request::concept::Concept,
+    ) -> Future<model::Concept> {
+        put!(
+            self,
+            route!(
+                "/models/datasets/{dataset_id}/concepts/{concept_id}",
+                dataset_id,
+                concept_id
+            ),
+            params!(),
+            payload!(concept)
+        )
+    }
+
+    /// Delete a metadata concept. A concept with records cannot be deleted.
+    pub fn delete_concept(&self, dataset_id: DatasetNodeId, concept_id: ConceptId) -> Future<()> {
+        let f = delete!(
+            self,
+            route!(
+                "/models/datasets/{dataset_id}/concepts/{concept_id}",
+                dataset_id,
+                concept_id
+            )
+        )
+        .map(|_: Nothing| ());
+        into_future_trait(f)
+    }
+
+    /// Get the schema of a metadata concept.
+    pub fn get_concept_properties(
+        &self,
+        dataset_id: DatasetNodeId,
+        concept_id: ConceptId,
+    ) -> Future<Vec<model::ConceptProperty>> {
+        get!(
+            self,
+            route!(
+                "/models/datasets/{dataset_id}/concepts/{concept_id}/properties",
+                dataset_id,
+                concept_id
+            )
+        )
+    }
+
+    /// Create or update the properties of a metadata concept. Properties
+    /// not listed are left as they are.
+    pub fn set_concept_properties(
+        &self,
+        dataset_id: DatasetNodeId,
+        concept_id: ConceptId,
+        properties: Vec<model::ConceptProperty>,
+    ) -> Future<Vec<model::ConceptProperty>> {
+        put!(
+            self,
+            route!(
+                "/models/datasets/{dataset_id}/concepts/{concept_id}/properties",
+                dataset_id,
+                concept_id
+            ),
+            params!(),
+            payload!(properties)
+        )
+    }
+
+    /// Get the records of a metadata concept, with their values typed by
+    /// the schema of the concept.
+    pub fn get_records(
+        &self,
+        dataset_id: DatasetNodeId,
+        concept_id: ConceptId,
+    ) -> Future<Vec<model::Record>> {
+        let schema = self.get_concept_properties(dataset_id.clone(), concept_id.clone());
+        let records = get!(
+            self,
+            route!(
+                "/models/datasets/{dataset_id}/concepts/{concept_id}/instances",
+                dataset_id,
+                concept_id
+            )
+        );
+        let f = schema
+            .join(records)
+            .and_then(|(schema, records)| with_schema(records, &schema));
+        into_future_trait(f)
+    }
+
+    /// Create several records of a metadata concept at once. Every record
+    /// is checked against the schema of the concept before any is created.
+    pub fn create_records(
+        &self,
+        dataset_id: DatasetNodeId,
+        concept_id: ConceptId,
+        records: Vec<request::concept::Record>,
+    ) -> Future<Vec<model::Record>> {
+        let bf = self.clone();
+
+        let f = self
+            .get_concept_properties(dataset_id.clone(), concept_id.clone())
+            .and_then(move |schema| {
+                for record in &records {
+                    record.validate(&schema)?;
+                }
+                Ok((schema, records))
+            })
+            .and_then(move |(schema, records)| {
+                post!(
+                    bf,
+                    route!(
+                        "/models/datasets/{dataset_id}/concepts/{concept_id}/instances/batch",
+                        dataset_id,
+                        concept_id
+                    ),
+                    params!(),
+                    payload!(records)
+                )
+                .and_then(move |records: Vec<model::Record>| with_schema(records, &schema))
+            });
+
+        into_future_trait(f)
+    }
+
+    /// Replace the values of a record. The record is checked against the
+    /// schema of the concept first.
+    pub fn update_record(
+        &self,
+        dataset_id: DatasetNodeId,
+        concept_id: ConceptId,
+        record_id: RecordId,
+        record: request::concept::Record,
+    ) -> Future<model::Record> {
+        let f = self
+            .update_records(dataset_id, concept_id, vec![(record_id, record)])
+            .map(|mut records| records.remove(0));
+        into_future_trait(f)
+    }
+
+    /// Replace the values of several records of a metadata concept, one
+    /// after the other. Every record is checked against the schema of the
+    /// concept before any is updated.
+    ///
+    /// If updating a record fails, the update stops with
+    /// `ErrorKind::RecordUpdateFailed`, which lists the records updated
+    /// before it.
+    pub fn update_records(
+        &self,
+        dataset_id: DatasetNodeId,
+        concept_id: ConceptId,
+        records: Vec<(RecordId, request::concept::Record)>,
+    ) -> Future<Vec<model::Record>> {
+        let bf = self.clone();
+
+        let f = self
+            .get_concept_properties(dataset_id.clone(), concept_id.clone())
+            .and_then(move |schema| {
+                for (_, record) in &records {
+                    record.validate(&schema)?;
+                }
+                Ok((schema, records))
+            })
+            .and_then(move |(schema, records)| {
+                stream::iter_ok(records)
+                    .fold(vec![], move |mut updated, (record_id, record)| {
+                        let dataset_id = dataset_id.clone();
+                        let concept_id = concept_id.clone();
+                        put!(
+                            bf,
+                            route!(
+                                "/models/datasets/{dataset_id}/concepts/{concept_id}/instances/{record_id}",
+                                dataset_id,
+                                concept_id,
+                                record_id
+                            ),
+                            params!(),
+                            payload!(record)
+                        )
+                        .then(move |result: Result<model::Record>| match result {
+                            Ok(record) => {
+                                updated.push(record);
+                                Ok(updated)
+                            }
+                            Err(err) => {
+                                let updated = updated.iter().map(|r| r.id().clone()).collect();
+                                Err(Error::record_update_failed(updated, &err))
+                            }
+                        })
+                    })
+                    .and_then(move |records: Vec<model::Record>| with_schema(records, &schema))
+            });
+
+        into_future_trait(f)
+    }
+
+    /// Delete several records of a metadata concept.
+    pub fn delete_records(
+        &self,
+        dataset_id: DatasetNodeId,
+        concept_id: ConceptId,
+        record_ids: Vec<RecordId>,
+    ) -> Future<()> {
+        let f = delete!(
+            self,
+            route!(
+                "/models/datasets/{dataset_id}/concepts/{concept_id}/instances",
+                dataset_id,
+                concept_id
+            ),
+            params!(),
+            payload!(request::concept::DeleteRecords::new(record_ids))
+        )
+        .map(|_: Nothing| ());
+        into_future_trait(f)
+    }
+
+    /// Find the records of a metadata concept matching a query, with their
+    /// values typed by the schema of the concept.
+    pub fn query_records(
+        &self,
+        dataset_id: DatasetNodeId,
+        concept_id: ConceptId,
+        query: request::concept::RecordQuery,
+    ) -> Future<Vec<model::Record>> {
+        let schema = self.get_concept_properties(dataset_id.clone(), concept_id.clone());
+        let records = post!(
+            self,
+            route!(
+                "/models/datasets/{dataset_id}/concepts/{concept_id}/instances/query",
+                dataset_id,
+                concept_id
+            ),
+            params!(),
+            payload!(query)
+        );
+        let f = schema
+            .join(records)
+            .and_then(|(schema, records)| with_schema(records, &schema));
+        into_future_trait(f)
+    }
+
+    /// Create the concept a type is stored as, or migrate its schema by
+    /// creating and updating properties so it matches
+    /// `RecordSchema::properties`.
+    pub fn sync_schema<T: RecordSchema>(
+        &self,
+        dataset_id: DatasetNodeId,
+    ) -> Future<model::Concept> {
+        let bf = self.clone();
+        let name = T::concept_name();
+
+        let f = self
+            .get_concepts(dataset_id.clone())
+            .and_then(move |concepts| {
+                match concepts.into_iter().find(|c| c.name() == &name) {
+                    Some(concept) => into_future_trait(future::ok(concept)),
+                    None => {
+                        bf.create_concept(dataset_id.clone(), request::concept::Concept::new(name))
+                    }
+                }
+                .map(move |concept| (bf, dataset_id, concept))
+            })
+            .and_then(|(bf, dataset_id, concept)| {
+                bf.get_concept_properties(dataset_id.clone(), concept.id().clone())
+                    .and_then(|current| schema::schema_changes(&current, &T::properties()))
+                    .and_then(move |changes| {
+                        if changes.is_empty() {
+                            into_future_trait(future::ok(concept))
+                        } else {
+                            let f = bf
+                                .set_concept_properties(dataset_id, concept.id().clone(), changes)
+                                .map(|_| concept);
+                            into_future_trait(f)
+                        }
+                    })
+            });
+
+        into_future_trait(f)
+    }
+
+    /// Store several values as records of the concept of their type. See
+    /// `sync_schema`.
+    pub fn insert_records<T: RecordSchema>(
+        &self,
+        dataset_id: DatasetNodeId,
+        values: &[T],
+    ) -> Future<Vec<model::Record>> {
+        let records = match values.iter().map(T::to_record).collect::<Result<Vec<_>>>() {
+            Ok(records) => records,
+            Err(err) => return into_future_trait(future::err(err)),
+        };
+
+        let bf = self.clone();
+        let f = self
+            .get_schema_concept::<T>(dataset_id.clone())
+            .and_then(move |concept| bf.create_records(dataset_id, concept.id().clone(), records));
+
+        into_future_trait(f)
+    }
+
+    /// Find the records of the concept of a type matching a query, as
+    /// values of the type.
+    pub fn query<T: RecordSchema>(
+        &self,
+        dataset_id: DatasetNodeId,
+        query: request::concept::RecordQuery,
+    ) -> Future<Vec<T>> {
+        let bf = self.clone();
+        let f = self
+            .get_schema_concept::<T>(dataset_id.clone())
+            .and_then(move |concept| bf.query_records(dataset_id, concept.id().clone(), query))
+            .and_then(|records| {
+                records
+                    .iter()
+                    .map(T::from_record)
+                    .collect::<Result<Vec<_>>>()
+            });
+
+        into_future_trait(f)
+    }
+
+    /// Get the concept values of a type are stored as.
+    fn get_schema_concept<T: RecordSchema>(
+        &self,
+        dataset_id: DatasetNodeId,
+    ) -> Future<model::Concept> {
+        let name = T::concept_name();
+        let f = self.get_concepts(dataset_id).and_then(move |concepts| {
+            concepts
+                .into_iter()
+                .find(|c| c.name() == &name)
+                .ok_or_else(|| {
+                    Error::invalid_arguments(format!(
+                        "no such concept: {} (see `Blackfynn::sync_schema`)",
+                        name
+                    ))
+                })
+        });
+        into_future_trait(f)
+    }
+
+    /// Link several records to a package, such as the recordings of a
+    /// subject.
+    pub fn link_records_to_package(
+        &self,
+        dataset_id: DatasetNodeId,
+        package_id: PackageId,
+        record_ids: Vec<RecordId>,
+    ) -> Future<()> {
+        let f = post!(
+            self,
+            route!(
+                "/models/datasets/{dataset_id}/proxy/package/instances",
+                dataset_id
+            ),
+            params!(),
+            payload!(request::concept::PackageLink::new(
+                package_id,
+                record_ids,
+                request::concept::BELONGS_TO
+            ))
+        )
+        .map(|_: Nothing| ());
+        into_future_trait(f)
+    }
+
+    /// Get the types of relationship between the records of a dataset.
+    pub fn get_relationship_types(
+        &self,
+        dataset_id: DatasetNodeId,
+    ) -> Future<Vec<model::RelationshipType>> {
+        get!(
+            self,
+            route!("/models/datasets/{dataset_id}/relationships", dataset_id)
+        )
+    }
+
+    /// Create a type of relationship between the records of a dataset.
+    pub fn create_relationship_type(
+        &self,
+        dataset_id: DatasetNodeId,
+        relationship_type: request::concept::RelationshipType,
+    ) -> Future<model::RelationshipType> {
+        post!(
+            self,
+            route!("/models/datasets/{dataset_id}/relationships", dataset_id),
+            params!(),
+            payload!(relationship_type)
+        )
+    }
+
+    /// Get the relationships of a type between records.
+    pub fn get_record_relationships(
+        &self,
+        dataset_id: DatasetNodeId,
+        relationship_id: RelationshipId,
+    ) -> Future<Vec<model::RecordRelationship>> {
+        get!(
+            self,
+            route!(
+                "/models/datasets/{dataset_id}/relationships/{relationship_id}/instances",
+                dataset_id,
+                relationship_id
+            )
+        )
+    }
+
+    /// Relate several pairs of records at once, each `(from, to)`.
+    pub fn link_records(
+        &self,
+        dataset_id: DatasetNodeId,
+        relationship_id: RelationshipId,
+        links: Vec<(RecordId, RecordId)>,
+    ) -> Future<Vec<model::RecordRelationship>> {
+        let links: Vec<request::concept::RecordLink> = links
+            .into_iter()
+            .map(|(from, to)| request::concept::RecordLink::new(from, to))
+            .collect();
+        post!(
+            self,
+            route!(
+                "/models/datasets/{dataset_id}/relationships/{relationship_id}/instances/batch",
+                dataset_id,
+                relationship_id
+            ),
+            params!(),
+            payload!(links)
+        )
+    }
+
+    /// Delete several relationships between records.
+    pub fn unlink_records(
+        &self,
+        dataset_id: DatasetNodeId,
+        relationship_id: RelationshipId,
+        ids: Vec<String>,
+    ) -> Future<()> {
+        let f = delete!(
+            self,
+            route!(
+                "/models/datasets/{dataset_id}/relationships/{relationship_id}/instances",
+                dataset_id,
+                relationship_id
+            ),
+            params!(),
+            payload!(request::concept::DeleteRelationships::new(ids))
+        )
+        .map(|_: Nothing| ());
+        into_future_trait(f)
+    }
+
+    /// Move several packages to a destination package.
+    /// If destination is None, the package is moved to the top level of the dataset.
+    pub fn mv<T: Into<PackageId>, D: Into<PackageId>>(
+        &self,
+        things: Vec<T>,
+        destination: Option<D>,
+    ) -> Future<response::MoveResponse> {
+        // Cached datasets include their packages:
+        let bf = self.clone();
+        let f = post!(
+            self,
+            "/data/move",
+            params!(),
+            payload!(request::mv::Move::new(things, destination))
+        )
+        .then(move |result| {
+            bf.invalidate_dataset(None);
+            result
+        });
+        into_future_trait(f)
+    }
+
+    /// Delete several packages. Collections that still contain packages
+    /// are not deleted; see `delete_packages_with_options`.
+    pub fn delete_packages<T: Into<PackageId>>(
+        &self,
+        things: Vec<T>,
+    ) -> Future<response::DeleteResponse> {
+        self.delete_packages_with_options(things, false, false)
+    }
+
+    /// Delete several packages.
+    ///
+    /// Unless `recursive` is set, collections that still contain packages
+    /// are reported as failures instead of being deleted along with their
+    /// contents. With `dry_run` set nothing is deleted; the response lists
+    /// every package that would be removed, including the contents of
+    /// collections.
+    pub fn delete_packages_with_options<T: Into<PackageId>>(
+        &self,
+        things: Vec<T>,
+        recursive: bool,
+        dry_run: bool,
+    ) -> Future<response::DeleteResponse> {
+        let bf = self.clone();
+
+        let lookups: Vec<_> = things
+            .into_iter()
+            .map(Into::into)
+            .map(|id: PackageId| {
+                self.get_package_by_id(id.clone())
+                    .then(move |lookup| future::ok::<_, Error>(deletable(id, lookup, recursive)))
+            })
+            .collect();
+
+        let f = future::join_all(lookups).and_then(move |lookups| {
+            let mut packages = vec![];
+            let mut failures = vec![];
+            for lookup in lookups {
+                match lookup {
+                    Ok(package) => packages.push(package),
+                    Err(failure) => failures.push(failure),
+                }
+            }
+
+            if dry_run {
+                let listings: Vec<_> = packages
+                    .iter()
+                    .map(|package| {
+                        let id = package.id().clone();
+                        bf.get_descendant_ids(package).map(move |mut ids| {
+                            ids.insert(0, id);
+                            ids
+                        })
+                    })
+                    .collect();
+                into_future_trait(future::join_all(listings).map(move |ids| {
+                    let success = ids.into_iter().flatten().map(Into::into).collect();
+                    response::DeleteResponse::new(success, failures)
+                }))
+            } else if packages.is_empty() {
+                into_future_trait(future::ok(response::DeleteResponse::new(vec![], failures)))
+            } else {
+                let ids: Vec<PackageId> = packages.iter().map(|p| p.id().clone()).collect();
+                let f = post!(
+                    bf,
+                    "/data/delete",
+                    params!(),
+                    payload!(request::delete::Delete::new(ids))
+                )
+                .map(move |response: response::DeleteResponse| {
+                    failures.extend(response.failures().iter().cloned());
+                    response::DeleteResponse::new(response.success().clone(), failures)
+                })
+                .then(move |result| {
+                    // Cached datasets include their packages:
+                    bf.invalidate_dataset(None);
+                    result
+                });
+                into_future_trait(f)
+            }
+        });
+
+        into_future_trait(f)
+    }
+
+    /// Get the IDs of every package contained in a package, at any depth.
+    fn get_descendant_ids(&self, package: &response::Package) -> Future<Vec<PackageId>> {
+        let bf = self.clone();
+        let pending: VecDeque<response::Package> =
+            package.children().cloned().unwrap_or_default().into();
+
+        let f = future::loop_fn(
+            (vec![], pending),
+            move |(mut ids, mut pending)| match pending.pop_front() {
+                Some(child) => {
+                    ids.push(child.id().clone());
+                    if child.is_collection() {
+                        into_future_trait(bf.get_package_by_id(child.id().clone()).map(
+                            move |collection| {
+                                pending.extend(collection.children().cloned().unwrap_or_default());
+                                future::Loop::Continue((ids, pending))
+                            },
+                        ))
+                    } else {
+                        into_future_trait(future::ok(future::Loop::Continue((ids, pending))))
+                    }
+                }
+                None => into_future_trait(future::ok(future::Loop::Break(ids))),
+            },
+        );
+
+        into_future_trait(f)
+    }
+
+    /// Get the ID of the collection at `path`, a `/`-separated list of
+    /// collection names relative to the top level of a dataset, creating
+    /// every missing collection along the way.
+    pub fn ensure_collection_path<P: AsRef<str>>(
+        &self,
+        dataset_id: &DatasetNodeId,
+        path: P,
+    ) -> Future<PackageId> {
+        let components = path_components(path.as_ref());
+        if components.is_empty() {
+            return into_future_trait(future::err(Error::invalid_arguments(
+                "a collection path needs at least one collection name",
+            )));
+        }
+
+        let bf = self.clone();
+        let dataset_id = dataset_id.clone();
+
+        let f = self
+            .get_dataset_by_id(dataset_id.clone())
+            .and_then(move |dataset| {
+                let children = dataset.children().cloned().unwrap_or_default();
+                future::loop_fn(
+                    (None, children, components.into_iter()),
+                    move |(parent, children, mut components): (Option<PackageId>, _, _)| {
+                        let name = match components.next() {
+                            Some(name) => name,
+                            None => {
+                                let leaf = parent.expect("at least one collection name");
+                                return into_future_trait(future::ok(future::Loop::Break(leaf)));
+                            }
+                        };
+
+                        let existing = children
+                            .into_iter()
+                            .find(|child: &response::Package| {
+                                child.name() == &name && child.is_collection()
+                            })
+                            .map(|child| child.id().clone());
+
+                        match existing {
+                            Some(id) => into_future_trait(bf.get_package_by_id(id.clone()).map(
+                                move |collection| {
+                                    let children =
+                                        collection.children().cloned().unwrap_or_default();
+                                    future::Loop::Continue((Some(id), children, components))
+                                },
+                            )),
+                            None => into_future_trait(
+                                bf.create_package(name, "Collection", dataset_id.clone(), parent)
+                                    .map(move |collection| {
+                                        let id = collection.id().clone();
+                                        future::Loop::Continue((Some(id), vec![], components))
+                                    }),
+                            ),
+                        }
+                    },
+                )
+            });
+
+        into_future_trait(f)
+    }
+
+    /// Get the package at `path`, a `/`-separated list of names relative
+    /// to the top level of a dataset. Every name but the last must be that
+    /// of a collection. The last name is matched against package names
+    /// with and without its file extension, as packages created from
+    /// uploaded files are named after the file without its extension.
+    pub fn resolve_path<P: AsRef<str>>(
+        &self,
+        dataset_id: &DatasetNodeId,
+        path: P,
+    ) -> Future<response::Package> {
+        let mut components = path_components(path.as_ref());
+        let full_path = components.join("/");
+        let name = match components.pop() {
+            Some(name) => name,
+            None => return into_future_trait(future::err(Error::invalid_package_path(full_path))),
+        };
+
+        let bf = self.clone();
+
+        let f = self
+            .get_children_at_path(dataset_id, None, components)
+            .and_then(move |listing| {
+                let children = listing.map(|(_, children)| children).unwrap_or_default();
+                let stem = Path::new(&name)
+                    .file_stem()
+                    .and_then(|stem| stem.to_str())
+                    .map(String::from);
+                let package = children
+                    .iter()
+                    .find(|child| child.name() == &name)
+                    .or_else(|| {
+                        children
+                            .iter()
+                            .find(|child| Some(child.name()) == stem.as_ref())
+                    });
+
+                match package {
+                    Some(package) => into_future_trait(bf.get_package_by_id(package.id().clone())),
+                    None => into_future_trait(future::err(Error::invalid_package_path(full_path))),
+                }
+            });
+
+        into_future_trait(f)
+    }
+
+    /// Walk every package of a dataset, breadth first, yielding each
+    /// package along with its `/`-separated path from the top level of
+    /// the dataset. Collections are yielded before their contents.
+    pub fn walk(&self, dataset_id: &DatasetNodeId) -> Stream<(String, response::Package)> {
+        let bf = self.clone();
+
+        let s = self
+            .get_dataset_by_id(dataset_id.clone())
+            .map(move |dataset| {
+                let pending: VecDeque<(String, response::Package)> = dataset
+                    .children()
+                    .cloned()
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|child| (child.name().clone(), child))
+                    .collect();
+
+                stream::unfold(pending, move |mut pending| {
+                    pending.pop_front().map(|(path, package)| {
+                        if package.is_collection() {
+                            into_future_trait(bf.get_package_by_id(package.id().clone()).map(
+                                move |collection| {
+                                    for child in collection.children().cloned().unwrap_or_default()
+                                    {
+                                        let child_path = format!("{}/{}", path, child.name());
+                                        pending.push_back((child_path, child));
+                                    }
+                                    ((path, collection), pending)
+                                },
+                            ))
+                        } else {
+                            into_future_trait(future::ok(((path, package), pending)))
+                        }
+                    })
+                })
+            })
+            .flatten_stream();
+
+        into_stream_trait(s)
+    }
+
+    /// Get the members that belong to the current users organization.
+    pub fn get_members(&self) -> Future<Vec<model::User>> {
+        into_future_trait(match self.current_organization() {
+            Some(org) => self.get_members_by_organization(org),
+            None => into_future_trait(future::err::<_, Error>(ErrorKind::NoOrganizationSet.into())),
+        })
+    }
+
+    /// Get the members that belong to the specified organization.
+    pub fn get_members_by_organization(&self, id: OrganizationId) -> Future<Vec<model::User>> {
+        let key = String::from(id.clone());
+        self.cached_get(
+            CachedResource::Members,
+            key,
+            route!("/organizations/{id}/members", id),
+        )
+    }
+
+    /// Fail with `ErrorKind::Forbidden` unless the current user is an
+    /// administrator of the organization.
+    fn require_organization_admin(&self, id: OrganizationId) -> Future<()> {
+        let f = self
+            .get_organization_by_id(id)
+            .and_then(|org| require_admin(&org));
+        into_future_trait(f)
+    }
+
+    /// Invite several people to join an organization. Requires
+    /// administrator privileges.
+    ///
+    /// Returns the outcome of each invitation, keyed by email address.
+    pub fn invite_organization_members(
+        &self,
+        id: OrganizationId,
+        invites: Vec<request::organization::Invite>,
+    ) -> Future<HashMap<String, response::ChangeResponse>> {
+        let bf = self.clone();
+        let organization_id = id.clone();
+        let f = self.require_organization_admin(id.clone()).and_then(
+            move |_| -> Future<HashMap<String, response::ChangeResponse>> {
+                // Existing users are added as members right away:
+                into_future_trait(
+                    post!(
+                        bf.clone(),
+                        route!("/organizations/{id}/members", id),
+                        params!(),
+                        payload!(request::organization::Invites::new(invites))
+                    )
+                    .then(move |result| {
+                        bf.invalidate_organization(Some(&organization_id));
+                        result
+                    }),
+                )
+            },
+        );
+        into_future_trait(f)
+    }
+
+    /// Get the invitations to join an organization that have not been
+    /// accepted yet. Requires administrator privileges.
+    pub fn get_organization_invites(&self, id: OrganizationId) -> Future<Vec<response::Invite>> {
+        let bf = self.clone();
+        let f = self.require_organization_admin(id.clone()).and_then(
+            move |_| -> Future<Vec<response::Invite>> {
+                get!(bf, route!("/organizations/{id}/invites", id))
+            },
+        );
+        into_future_trait(f)
+    }
+
+    /// Send an invitation to join an organization again, extending its
+    /// validity. Requires administrator privileges.
+    pub fn resend_organization_invite<I: Into<String>>(
+        &self,
+        id: OrganizationId,
+        invite_id: I,
+    ) -> Future<response::Invite> {
+        let bf = self.clone();
+        let invite_id = invite_id.into();
+        let f = self.require_organization_admin(id.clone()).and_then(
+            move |_| -> Future<response::Invite> {
+                put!(
+                    bf,
+                    route!("/organizations/{id}/invites/{invite_id}", id, invite_id)
+                )
+            },
+        );
+        into_future_trait(f)
+    }
+
+    /// Cancel an invitation to join an organization. Requires
+    /// administrator privileges.
+    pub fn cancel_organization_invite<I: Into<String>>(
+        &self,
+        id: OrganizationId,
+        invite_id: I,
+    ) -> Future<()> {
+        let bf = self.clone();
+        let invite_id = invite_id.into();
+        let f = self
+            .require_organization_admin(id.clone())
+            .and_then(move |_| -> Future<Nothing> {
+                delete!(
+                    bf,
+                    route!("/organizations/{id}/invites/{invite_id}", id, invite_id)
+                )
+            })
+            .map(|_| ());
+        into_future_trait(f)
+    }
+
+    /// Change the role of a member of an organization. Requires
+    /// administrator privileges.
+    pub fn set_organization_member_role(
+        &self,
+        id: OrganizationId,
+        user_id: UserId,
+        role: Role,
+    ) -> Future<model::User> {
+        let bf = self.clone();
+        let organization_id = id.clone();
+        let f =
+            self.require_organization_admin(id.clone())
+                .and_then(move |_| -> Future<model::User> {
+                    into_future_trait(
+                        put!(
+                            bf.clone(),
+                            route!("/organizations/{id}/members/{user_id}", id, user_id),
+                            params!(),
+                            payload!(request::organization::UpdateMember::new(role))
+                        )
+                        .then(move |result| {
+                            bf.invalidate_organization(Some(&organization_id));
+                            result
+                        }),
+                    )
+                });
+        into_future_trait(f)
+    }
+
+    /// Remove a member from an organization. Requires administrator
+    /// privileges.
+    pub fn remove_organization_member(&self, id: OrganizationId, user_id: UserId) -> Future<()> {
+        let bf = self.clone();
+        let organization_id = id.clone();
+        let f = self
+            .require_organization_admin(id.clone())
+            .and_then(move |_| -> Future<Nothing> {
+                into_future_trait(
+                    delete!(
+                        bf.clone(),
+                        route!("/organizations/{id}/members/{user_id}", id, user_id)
+                    )
+                    .then(move |result| {
+                        bf.invalidate_organization(Some(&organization_id));
+                        result
+                    }),
+                )
+            })
+            .map(|_| ());
+        into_future_trait(f)
+    }
+
+    /// Get the members that belong to the current users organization.
+    pub fn get_teams(&self) -> Future<Vec<response::Team>> {
+        into_future_trait(match self.current_organization() {
+            Some(org) => self.get_teams_by_organization(org),
+            None => into_future_trait(future::err::<_, Error>(ErrorKind::NoOrganizationSet.into())),
+        })
+    }
+
+    /// Get the teams that belong to the specified organization.
+    pub fn get_teams_by_organization(&self, id: OrganizationId) -> Future<Vec<response::Team>> {
+        get!(self, route!("/organizations/{id}/teams", id))
+    }
+
+    /// Get the members of a team.
+    pub fn get_team_members(
+        &self,
+        organization_id: OrganizationId,
+        team_id: TeamId,
+    ) -> Future<Vec<model::User>> {
+        get!(
+            self,
+            route!(
+                "/organizations/{organization_id}/teams/{team_id}/members",
+                organization_id,
+                team_id
+            )
+        )
+    }
+
+    /// Create a team in an organization.
+    pub fn create_team<N: Into<String>>(
+        &self,
+        organization_id: OrganizationId,
+        name: N,
+    ) -> Future<response::Team> {
+        post!(
+            self,
+            route!("/organizations/{organization_id}/teams", organization_id),
+            params!(),
+            payload!(request::team::Team::new(name))
+        )
+    }
+
+    /// Rename a team.
+    pub fn rename_team<N: Into<String>>(
+        &self,
+        organization_id: OrganizationId,
+        team_id: TeamId,
+        name: N,
+    ) -> Future<response::Team> {
+        put!(
+            self,
+            route!(
+                "/organizations/{organization_id}/teams/{team_id}",
+                organization_id,
+                team_id
+            ),
+            params!(),
+            payload!(request::team::Team::new(name))
+        )
+    }
+
+    /// Delete a team.
+    pub fn delete_team(&self, organization_id: OrganizationId, team_id: TeamId) -> Future<()> {
+        let f = delete!(
+            self,
+            route!(
+                "/organizations/{organization_id}/teams/{team_id}",
+                organization_id,
+                team_id
+            )
+        )
+        .map(|_: Nothing| ());
+        into_future_trait(f)
+    }
+
+    /// Add several members of the organization to a team.
+    pub fn add_team_members(
+        &self,
+        organization_id: OrganizationId,
+        team_id: TeamId,
+        users: Vec<UserId>,
+    ) -> Future<Vec<model::User>> {
+        post!(
+            self,
+            route!(
+                "/organizations/{organization_id}/teams/{team_id}/members",
+                organization_id,
+                team_id
+            ),
+            params!(),
+            payload!(request::team::AddMembers::new(users))
+        )
+    }
+
+    /// Add several members of the organization to a team, looking them
+    /// up by email address.
+    pub fn add_team_members_by_email<E: Into<String>>(
+        &self,
+        organization_id: OrganizationId,
+        team_id: TeamId,
+        emails: Vec<E>,
+    ) -> Future<Vec<model::User>> {
+        let bf = self.clone();
+        let emails: Vec<String> = emails.into_iter().map(Into::into).collect();
+
+        let f = self
+            .get_members_by_organization(organization_id.clone())
+            .and_then(move |members| {
+                user_ids_by_email(&members, &emails, "organization")
+                    .into_future()
+                    .and_then(move |users| bf.add_team_members(organization_id, team_id, users))
+            });
+
+        into_future_trait(f)
+    }
+
+    /// Remove a member from a team.
+    pub fn remove_team_member(
+        &self,
+        organization_id: OrganizationId,
+        team_id: TeamId,
+        user_id: UserId,
+    ) -> Future<()> {
+        let f = delete!(
+            self,
+            route!(
+                "/organizations/{organization_id}/teams/{team_id}/members/{user_id}",
+                organization_id,
+                team_id,
+                user_id
+            )
+        )
+        .map(|_: Nothing| ());
+        into_future_trait(f)
+    }
+
+    /// Remove a member from a team, looking them up by email address.
+    pub fn remove_team_member_by_email<E: Into<String>>(
+        &self,
+        organization_id: OrganizationId,
+        team_id: TeamId,
+        email: E,
+    ) -> Future<()> {
+        let bf = self.clone();
+        let emails = vec![email.into()];
+
+        let f = self
+            .get_team_members(organization_id.clone(), team_id.clone())
+            .and_then(move |members| {
+                user_ids_by_email(&members, &emails, "team")
+                    .into_future()
+                    .and_then(move |mut users| {
+                        bf.remove_team_member(organization_id, team_id, users.remove(0))
+                    })
+            });
+
+        into_future_trait(f)
+    }
+
+    /// Promote a member of a team to administrator of the team, or demote
+    /// an administrator back to a regular member.
+    pub fn set_team_administrator(
+        &self,
+        organization_id: OrganizationId,
+        team_id: TeamId,
+        user_id: UserId,
+        administrator: bool,
+    ) -> Future<model::User> {
+        put!(
+            self,
+            route!(
+                "/organizations/{organization_id}/teams/{team_id}/members/{user_id}",
+                organization_id,
+                team_id,
+                user_id
+            ),
+            params!(),
+            payload!(request::team::UpdateMember::new(administrator))
+        )
+    }
+
+    /// Generate a preview of the files to be uploaded.
+    pub fn preview_upload<P, Q>(
+        &self,
+        organization_id: &OrganizationId,
+        dataset_id: &DatasetId,
         path: Option<P>,
         files: &[(UploadId, Q)],
         append: bool,
@@ -860,345 +3597,1377 @@ impl Blackfynn {
                 ),
                 &request::UploadPreview::new(&s3_files)
             )
-        });
+        });
+
+        into_future_trait(post)
+    }
+
+    /// Generate a preview of a recursive directory upload, skipping any
+    /// files filtered out by the given options.
+    pub fn preview_directory_upload<P>(
+        &self,
+        organization_id: &OrganizationId,
+        dataset_id: &DatasetId,
+        path: P,
+        options: &model::DirectoryUploadOptions,
+        append: bool,
+    ) -> Future<response::UploadPreview>
+    where
+        P: AsRef<Path>,
+    {
+        match options.list_files(path.as_ref()) {
+            Ok(files) => self.preview_upload(
+                organization_id,
+                dataset_id,
+                Some(path),
+                &files,
+                append,
+                true,
+            ),
+            Err(err) => into_future_trait(future::err(err)),
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    /// Upload a batch of files using the upload service.
+    pub fn upload_file_chunks<P, C>(
+        &self,
+        organization_id: &OrganizationId,
+        import_id: &ImportId,
+        path: P,
+        files: Vec<model::S3File>,
+        missing_parts: Option<response::FilesMissingParts>,
+        progress_callback: C,
+        parallelism: usize,
+    ) -> Stream<ImportId>
+    where
+        P: 'static + AsRef<Path>,
+        C: 'static + ProgressCallback + Clone,
+    {
+        self.upload_file_chunks_with_cancellation(
+            organization_id,
+            import_id,
+            path,
+            files,
+            missing_parts,
+            progress_callback,
+            parallelism,
+            FileChecksums::default(),
+            CancellationToken::new(),
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    /// Like `upload_file_chunks`, recording the checksum of every chunk
+    /// sent in `checksums`. See `verify_upload`.
+    ///
+    /// Once `cancellation` is cancelled no new chunks are sent, while
+    /// chunks already in flight are allowed to complete.
+    pub fn upload_file_chunks_with_cancellation<P, C>(
+        &self,
+        organization_id: &OrganizationId,
+        import_id: &ImportId,
+        path: P,
+        files: Vec<model::S3File>,
+        missing_parts: Option<response::FilesMissingParts>,
+        progress_callback: C,
+        parallelism: usize,
+        checksums: FileChecksums,
+        cancellation: CancellationToken,
+    ) -> Stream<ImportId>
+    where
+        P: 'static + AsRef<Path>,
+        C: 'static + ProgressCallback + Clone,
+    {
+        let bf = self.clone();
+        let organization_id = organization_id.clone();
+        let import_id = import_id.clone();
+        let progress_callback = progress_callback.clone();
+
+        let missing_file_names: Option<Vec<String>> = missing_parts
+            .clone()
+            .map(|mp| mp.files.into_iter().map(|f| f.file_name).collect());
+
+        let fs = stream::futures_unordered(
+            files
+                .into_iter()
+                .filter(|file| match &missing_file_names {
+                    None => true,
+                    Some(mp) => mp.contains(file.file_name()),
+                })
+                .zip(iter::repeat(path.as_ref().to_path_buf()))
+                .map(|file| future::ok::<(model::S3File, PathBuf), Error>(file.clone())),
+        )
+        .map(move |(file, path): (model::S3File, PathBuf)| {
+            let file = file.clone();
+
+            progress_callback.on_event(&ProgressEvent::FileStarted {
+                import_id: import_id.clone(),
+                file_path: progress_path(&path, &file),
+                size: file.size(),
+            });
+
+            let chunked_file_payload = file_payload(
+                &import_id,
+                &path,
+                &file,
+                missing_parts.as_ref(),
+                checksums.clone(),
+            );
+
+            let bf = bf.clone();
+            let organization_id = organization_id.clone();
+            let import_id = import_id.clone();
+            let progress_callback = progress_callback.clone();
+            let cancellation = cancellation.clone();
+
+            chunked_file_payload
+                .take_while(move |_| future::ok(!cancellation.is_cancelled()))
+                .map(move |(file_chunk, progress_update)| {
+                    if let Some(MultipartUploadId(multipart_upload_id)) = file.multipart_upload_id()
+                    {
+                        let import_id = import_id.clone();
+                        let import_id_clone = import_id.clone();
+                        let organization_id = organization_id.clone();
+                        let progress_callback = progress_callback.clone();
+
+                        into_future_trait(
+                            bf.request_with_body(
+                                route!(
+                                    "/upload/chunk/organizations/{organization_id}/id/{import_id}",
+                                    organization_id,
+                                    import_id
+                                ),
+                                Method::POST,
+                                params!(
+                                    "filename" => file.file_name().to_string(),
+                                    "multipartId" => multipart_upload_id.to_string(),
+                                    "chunkChecksum" => file_chunk.checksum.0,
+                                    "chunkNumber" => file_chunk.chunk_number.to_string()
+                                ),
+                                file_chunk.bytes,
+                                vec![],
+                                false,
+                            )
+                            .and_then(
+                                move |response: response::UploadResponse| {
+                                    if response.success {
+                                        progress_callback
+                                            .on_event(&ProgressEvent::ChunkSent(progress_update));
+                                        future::ok(import_id_clone)
+                                    } else {
+                                        future::err(Error::upload_error(
+                                            response.error.unwrap_or_else(|| {
+                                                "no error message supplied".into()
+                                            }),
+                                        ))
+                                    }
+                                },
+                            ),
+                        )
+                    } else {
+                        into_future_trait(future::err(Error::upload_error(format!(
+                            "no multipartId was provided for file: {}",
+                            file.file_name()
+                        ))))
+                    }
+                })
+                .map_err(Into::into)
+                .buffer_unordered(parallelism)
+        })
+        .flatten();
+
+        into_stream_trait(fs)
+    }
+
+    /// Complete an upload to the upload service
+    pub fn complete_upload(
+        &self,
+        organization_id: &OrganizationId,
+        import_id: &ImportId,
+        dataset_id: &DatasetNodeId,
+        destination_id: Option<&PackageId>,
+        append: bool,
+    ) -> Future<response::Manifests> {
+        let mut params = params!(
+            "datasetId" => dataset_id,
+            "append" => if append { "true" } else { "false" }
+        );
+        if let Some(dest_id) = destination_id {
+            params.push(param!("destinationId", dest_id.clone()));
+        }
+
+        // Uploads are completed into new packages of the dataset:
+        let bf = self.clone();
+        let dataset_id = dataset_id.clone();
+        let f = post!(
+            self,
+            route!(
+                "/upload/complete/organizations/{organization_id}/id/{import_id}",
+                organization_id,
+                import_id
+            ),
+            params
+        )
+        .then(move |result| {
+            bf.invalidate_dataset(Some(&dataset_id));
+            result
+        });
+        into_future_trait(f)
+    }
+
+    /// Get the upload status using the upload service
+    pub fn get_upload_status(
+        &self,
+        organization_id: &OrganizationId,
+        import_id: &ImportId,
+    ) -> Future<Option<response::FilesMissingParts>> {
+        get!(
+            self,
+            route!(
+                "/upload/status/organizations/{organization_id}/id/{import_id}",
+                organization_id,
+                import_id
+            )
+        )
+    }
+
+    /// Get the hash of an uploaded file from the upload service
+    pub fn get_upload_hash<S>(
+        &self,
+        import_id: &ImportId,
+        file_name: S,
+    ) -> Future<response::FileHash>
+    where
+        S: Into<String>,
+    {
+        get!(
+            self,
+            route!("/upload/hash/id/{import_id}", import_id),
+            params!("fileName" => file_name)
+        )
+    }
+
+    /// Upload a batch of files using the upload service, resuming from the
+    /// missing parts reported by the service on failure.
+    ///
+    /// Once every chunk is sent, the files are verified against the hashes
+    /// reported by the upload service, failing with
+    /// `ErrorKind::ChecksumMismatch` if any differ.
+    pub fn upload_file_chunks_with_retries<P, C>(
+        &self,
+        organization_id: &OrganizationId,
+        import_id: &ImportId,
+        path: &P,
+        files: Vec<model::S3File>,
+        progress_callback: C,
+        parallelism: usize,
+    ) -> Stream<ImportId>
+    where
+        P: 'static + AsRef<Path> + Send,
+        C: 'static + ProgressCallback + Clone,
+    {
+        self.upload_file_chunks_with_retries_and_cancellation(
+            organization_id,
+            import_id,
+            path,
+            files,
+            progress_callback,
+            parallelism,
+            FileChecksums::default(),
+            CancellationToken::new(),
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    /// Like `upload_file_chunks_with_retries`, recording the checksum of
+    /// every chunk sent in `checksums`, so the files are verified without
+    /// reading them again.
+    ///
+    /// Once `cancellation` is cancelled no new chunks are sent and failed
+    /// chunks are not retried.
+    pub fn upload_file_chunks_with_retries_and_cancellation<P, C>(
+        &self,
+        organization_id: &OrganizationId,
+        import_id: &ImportId,
+        path: &P,
+        files: Vec<model::S3File>,
+        progress_callback: C,
+        parallelism: usize,
+        checksums: FileChecksums,
+        cancellation: CancellationToken,
+    ) -> Stream<ImportId>
+    where
+        P: 'static + AsRef<Path> + Send,
+        C: 'static + ProgressCallback + Clone,
+    {
+        let bf = self.clone();
+        let import_id = import_id.clone();
+        let path_buf = path.as_ref().to_path_buf();
+        let verified_files = files.clone();
+        let verified_checksums = checksums.clone();
+
+        let f = self
+            .upload_chunks_with_retries(
+                organization_id,
+                &import_id,
+                path,
+                files,
+                progress_callback,
+                parallelism,
+                checksums,
+                cancellation.clone(),
+            )
+            .collect()
+            .and_then(move |import_ids| {
+                if cancellation.is_cancelled() {
+                    return into_future_trait(future::err(Error::cancelled()));
+                }
+                let f = bf
+                    .verify_upload(&import_id, path_buf, &verified_files, &verified_checksums)
+                    .and_then(UploadReport::into_result)
+                    .map(move |_| stream::iter_ok(import_ids));
+                into_future_trait(f)
+            })
+            .flatten_stream();
 
-        into_future_trait(post)
+        into_stream_trait(f)
     }
 
     #[allow(clippy::too_many_arguments)]
-    /// Upload a batch of files using the upload service.
-    pub fn upload_file_chunks<P, C>(
+    /// The retry loop of `upload_file_chunks_with_retries`, without the
+    /// verification of the uploaded files.
+    fn upload_chunks_with_retries<P, C>(
         &self,
         organization_id: &OrganizationId,
         import_id: &ImportId,
-        path: P,
+        path: &P,
         files: Vec<model::S3File>,
-        missing_parts: Option<response::FilesMissingParts>,
         progress_callback: C,
         parallelism: usize,
+        checksums: FileChecksums,
+        cancellation: CancellationToken,
     ) -> Stream<ImportId>
     where
-        P: 'static + AsRef<Path>,
+        P: 'static + AsRef<Path> + Send,
         C: 'static + ProgressCallback + Clone,
     {
-        let bf = self.clone();
-        let organization_id = organization_id.clone();
-        let import_id = import_id.clone();
-        let progress_callback = progress_callback.clone();
+        #[derive(Clone)]
+        struct LoopDependencies<C: ProgressCallback + Clone> {
+            organization_id: OrganizationId,
+            import_id: ImportId,
+            path: PathBuf,
+            files: Vec<model::S3File>,
+            missing_parts: Option<response::FilesMissingParts>,
+            result: Option<Vec<ImportId>>,
+            progress_callback: C,
+            try_num: usize,
+            bf: Blackfynn,
+            parallelism: usize,
+            checksums: FileChecksums,
+            cancellation: CancellationToken,
+        }
 
-        let missing_file_names: Option<Vec<String>> = missing_parts
-            .clone()
-            .map(|mp| mp.files.into_iter().map(|f| f.file_name).collect());
+        impl<C: ProgressCallback + Clone> LoopDependencies<C> {
+            pub fn increment_attempt_count(self) -> Self {
+                Self {
+                    organization_id: self.organization_id,
+                    import_id: self.import_id,
+                    path: self.path,
+                    files: self.files,
+                    missing_parts: self.missing_parts,
+                    result: self.result,
+                    progress_callback: self.progress_callback,
+                    try_num: self.try_num + 1,
+                    bf: self.bf,
+                    parallelism: self.parallelism,
+                    checksums: self.checksums,
+                    cancellation: self.cancellation,
+                }
+            }
+        }
 
-        let fs = stream::futures_unordered(
-            files
-                .into_iter()
-                .filter(|file| match &missing_file_names {
-                    None => true,
-                    Some(mp) => mp.contains(file.file_name()),
+        let ld = LoopDependencies {
+            organization_id: organization_id.clone(),
+            import_id: import_id.clone(),
+            path: path.as_ref().to_path_buf(),
+            files,
+            missing_parts: None,
+            result: None,
+            progress_callback,
+            try_num: 0,
+            bf: self.clone(),
+            parallelism,
+            checksums,
+            cancellation,
+        };
+
+        let retry_loop = future::loop_fn(ld, |mut ld| {
+
+            let ld_err = ld.clone();
+
+            ld.bf
+                .get_upload_status(&ld.organization_id, &ld.import_id)
+                .map(|parts| {
+                    // Only the parts streamed in an earlier pass are
+                    // retried; the others were never attempted:
+                    if ld.try_num > 0 {
+                        for file in parts.iter().flat_map(|p| p.files.iter()) {
+                            let key = match ld.files.iter().find(|f| f.file_name() == &file.file_name)
+                            {
+                                Some(f) => f.relative_path(),
+                                None => continue,
+                            };
+                            for chunk_number in &file.missing_parts {
+                                if !ld.checksums.was_streamed(&key, *chunk_number) {
+                                    continue;
+                                }
+                                ld.progress_callback.on_event(&ProgressEvent::ChunkRetried {
+                                    import_id: ld.import_id.clone(),
+                                    file_path: ld.path.join(&key),
+                                    chunk_number: *chunk_number,
+                                    attempt: ld.try_num,
+                                });
+                            }
+                        }
+                    }
+                    ld.missing_parts = parts;
+                    ld
                 })
-                .zip(iter::repeat(path.as_ref().to_path_buf()))
-                .map(|file| future::ok::<(model::S3File, PathBuf), Error>(file.clone())),
-        )
-        .map(move |(file, path): (model::S3File, PathBuf)| {
-            let mut file_path = path.clone();
-            let file = file.clone();
+                .and_then(|ld| {
+                    ld.bf
+                        .upload_file_chunks_with_cancellation(
+                            &ld.organization_id,
+                            &ld.import_id,
+                            ld.path.clone(),
+                            ld.files.clone(),
+                            ld.missing_parts.clone(),
+                            ld.progress_callback.clone(),
+                            ld.parallelism,
+                            ld.checksums.clone(),
+                            ld.cancellation.clone(),
+                        )
+                        .collect()
+                        .map(future::Loop::Break)
+                })
+                .into_future()
+                .or_else(move |err| {
 
-            file_path.push(file.file_name());
+                    debug!("Upload encountered an error: {error}", error = err);
 
-            let file_missing_parts: Option<response::FileMissingParts> = match missing_parts {
-                Some(ref mp) => mp
-                    .files
-                    .iter()
-                    .find(|p| &p.file_name == file.file_name())
-                    .cloned(),
-                None => None,
-            };
+                    match err.kind() {
+                        // error cannot be retried, bubble up the error
+                        ErrorKind::ApiError{ status_code, .. } if NONRETRYABLE_STATUS_CODES.contains(status_code) => {
+                            debug!("Upload received status {status} from API which cannot be retried", status = status_code);
+                            into_future_trait(future::err(err))
+                        }
 
-            let chunked_file_payload = if let Some(chunked_upload_properties) =
-                file.chunked_upload()
-            {
-                debug!(
-                    "bf:upload_file_chunks<file = {file_name}> :: \
-                     Chunk size received from the upload service: {chunk_size}.",
-                    file_name = file.file_name(),
-                    chunk_size = chunked_upload_properties.chunk_size
-                );
-
-                ChunkedFilePayload::new_with_chunk_size(
-                    import_id.clone(),
-                    file_path,
-                    chunked_upload_properties.chunk_size,
-                    file_missing_parts.as_ref(),
-                )
-            } else {
-                debug!(
-                    "bf:upload_file_chunks<file = {file_name}> :: \
-                     No chunk size received from the upload service. \
-                     Falling back to default.",
-                    file_name = file.file_name()
-                );
-                ChunkedFilePayload::new(import_id.clone(), file_path, file_missing_parts.as_ref())
-            };
+                        // the upload was cancelled, bubble up the error
+                        _ if ld_err.cancellation.is_cancelled() => {
+                            debug!("Upload was cancelled, not retrying");
+                            into_future_trait(future::err(err))
+                        }
 
-            let bf = bf.clone();
-            let organization_id = organization_id.clone();
-            let import_id = import_id.clone();
-            let progress_callback = progress_callback.clone();
+                        // error that should be retried (if we are under MAX_RETRIES), retry the upload
+                        _ if MAX_RETRIES > ld_err.try_num => {
+                            let delay = retry_delay(ld_err.try_num);
 
-            chunked_file_payload
-                .map(move |(file_chunk, progress_update)| {
-                    if let Some(MultipartUploadId(multipart_upload_id)) = file.multipart_upload_id()
-                    {
-                        let import_id = import_id.clone();
-                        let import_id_clone = import_id.clone();
-                        let organization_id = organization_id.clone();
-                        let progress_callback = progress_callback.clone();
+                            debug!("Waiting {millis} millis to retry...", millis = delay);
 
-                        into_future_trait(
-                            bf.request_with_body(
-                                route!(
-                                    "/upload/chunk/organizations/{organization_id}/id/{import_id}",
-                                    organization_id,
-                                    import_id
-                                ),
-                                Method::POST,
-                                params!(
-                                    "filename" => file.file_name().to_string(),
-                                    "multipartId" => multipart_upload_id.to_string(),
-                                    "chunkChecksum" => file_chunk.checksum.0,
-                                    "chunkNumber" => file_chunk.chunk_number.to_string()
-                                ),
-                                file_chunk.bytes,
-                                vec![],
-                                false,
-                            )
-                            .and_then(
-                                move |response: response::UploadResponse| {
-                                    if response.success {
-                                        progress_callback.on_update(&progress_update.clone());
-                                        future::ok(import_id_clone)
-                                    } else {
-                                        future::err(Error::upload_error(
-                                            response.error.unwrap_or_else(|| {
-                                                "no error message supplied".into()
-                                            }),
-                                        ))
-                                    }
-                                },
-                            ),
-                        )
-                    } else {
-                        into_future_trait(future::err(Error::upload_error(format!(
-                            "no multipartId was provided for file: {}",
-                            file.file_name()
-                        ))))
+                            // delay
+                            let deadline = time::Instant::now() + time::Duration::from_millis(delay);
+                            let continue_loop = tokio::timer::Delay::new(deadline)
+                                .map_err(Into::<Error>::into)
+                                .map(move |_| {
+                                    debug!(
+                                        "Attempting to resume missing parts. Attempt {try_num}/{retries})...",
+                                        try_num = ld_err.try_num, retries = MAX_RETRIES
+                                    );
+                                    future::Loop::Continue(ld_err.increment_attempt_count())
+                                });
+                            into_future_trait(continue_loop)
+                        }
+
+                        // MAX_RETRIES exceeded, bubble up the error
+                        _ => {
+                            error!("Retries exceeded during upload. Bubbling up error {error}", error = err);
+                            into_future_trait(future::err(err))
+                        }
                     }
                 })
-                .map_err(Into::into)
-                .buffer_unordered(parallelism)
         })
+        .map(|import_ids| {
+            future::ok::<Stream<ImportId>, Error>(
+                into_stream_trait(stream::futures_unordered(
+                    import_ids
+                        .iter()
+                        .map(|import_id| future::ok(import_id.clone())),
+                )),
+            )
+                .into_stream()
+            .flatten()
+        })
+        .into_stream()
         .flatten();
 
-        into_stream_trait(fs)
+        into_stream_trait(retry_loop)
+    }
+
+    /// Verify the files of an import by comparing the hashes computed
+    /// locally while their chunks were sent against the hashes reported
+    /// by the upload service.
+    ///
+    /// Files whose hashes differ are listed in the resulting report with
+    /// an `ErrorKind::ChecksumMismatch` failure.
+    pub fn verify_upload<P>(
+        &self,
+        import_id: &ImportId,
+        path: P,
+        files: &[model::S3File],
+        checksums: &FileChecksums,
+    ) -> Future<UploadReport>
+    where
+        P: AsRef<Path>,
+    {
+        let local_hashes: Result<Vec<(String, String, String)>> = files
+            .iter()
+            .map(|file| {
+                let chunk_size = file
+                    .chunked_upload()
+                    .map(|properties| properties.chunk_size)
+                    .unwrap_or(DEFAULT_CHUNK_SIZE_BYTES);
+                checksums
+                    .file_hash(
+                        &file.relative_path(),
+                        path.as_ref().join(file.file_name()),
+                        chunk_size,
+                    )
+                    .map(|hash| (file.file_name().clone(), file.relative_path(), hash.0))
+                    .map_err(Into::into)
+            })
+            .collect();
+
+        let bf = self.clone();
+        let import_id = import_id.clone();
+        let report_import_id = import_id.clone();
+
+        let f = local_hashes
+            .into_future()
+            .and_then(move |local_hashes| {
+                let hash_futures =
+                    local_hashes
+                        .into_iter()
+                        .map(move |(file_name, relative_path, local_hash)| {
+                            bf.get_upload_hash(&import_id, file_name)
+                                .map(move |remote| {
+                                    FileReport::from_hashes(relative_path, local_hash, remote.hash)
+                                })
+                        });
+                future::join_all(hash_futures)
+            })
+            .map(move |files| UploadReport::new(report_import_id, files));
+
+        into_future_trait(f)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    /// Upload every file of a package preview, complete the import, and
+    /// verify the uploaded files against the hashes reported by the
+    /// upload service.
+    pub fn upload_package<P, C>(
+        &self,
+        organization_id: &OrganizationId,
+        dataset_id: &DatasetNodeId,
+        path: &P,
+        package: &model::PackagePreview,
+        destination_id: Option<&PackageId>,
+        append: bool,
+        progress_callback: C,
+        parallelism: usize,
+    ) -> Future<UploadReport>
+    where
+        P: 'static + AsRef<Path> + Send,
+        C: 'static + ProgressCallback + Clone,
+    {
+        self.upload_package_with_cancellation(
+            organization_id,
+            dataset_id,
+            path,
+            package,
+            destination_id,
+            append,
+            progress_callback,
+            parallelism,
+            CancellationToken::new(),
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    /// Like `upload_package`, but stops sending chunks once `cancellation`
+    /// is cancelled.
+    ///
+    /// A cancelled upload is not completed. Instead, a partial report is
+    /// returned listing the files left incomplete as
+    /// `FileStatus::Cancelled`, which can be passed to `resume_upload`
+    /// to send the remaining chunks. If an abort was requested, the
+    /// import is aborted on the upload service and every file is
+    /// reported as failed with `ErrorKind::Cancelled`.
+    pub fn upload_package_with_cancellation<P, C>(
+        &self,
+        organization_id: &OrganizationId,
+        dataset_id: &DatasetNodeId,
+        path: &P,
+        package: &model::PackagePreview,
+        destination_id: Option<&PackageId>,
+        append: bool,
+        progress_callback: C,
+        parallelism: usize,
+        cancellation: CancellationToken,
+    ) -> Future<UploadReport>
+    where
+        P: 'static + AsRef<Path> + Send,
+        C: 'static + ProgressCallback + Clone,
+    {
+        let bf = self.clone();
+        let organization_id = organization_id.clone();
+        let dataset_id = dataset_id.clone();
+        let destination_id = destination_id.cloned();
+        let import_id = package.import_id().clone();
+        let files = package.files().to_vec();
+        let path = path.as_ref().to_path_buf();
+        let checksums = FileChecksums::default();
+
+        let f = self
+            .upload_chunks_with_retries(
+                &organization_id,
+                &import_id,
+                &path.clone(),
+                files.clone(),
+                progress_callback.clone(),
+                parallelism,
+                checksums.clone(),
+                cancellation.clone(),
+            )
+            .collect()
+            .then(move |result| match result {
+                _ if cancellation.is_cancelled() => bf.cancelled_upload_report(
+                    &organization_id,
+                    &import_id,
+                    &files,
+                    cancellation.is_abort_requested(),
+                ),
+                Err(err) => into_future_trait(future::err(err)),
+                Ok(_) => into_future_trait(
+                    bf.complete_upload(
+                        &organization_id,
+                        &import_id,
+                        &dataset_id,
+                        destination_id.as_ref(),
+                        append,
+                    )
+                    .and_then(move |manifests| {
+                        progress_callback.on_event(&ProgressEvent::ImportCompleted {
+                            import_id: import_id.clone(),
+                        });
+                        let package_id = manifests.iter().find_map(|m| m.package_id()).cloned();
+                        bf.verify_upload(&import_id, path.clone(), &files, &checksums)
+                            .map(move |report| report.with_package_id(package_id))
+                            .map(move |report| {
+                                for file in report.iter() {
+                                    let import_id = report.import_id().clone();
+                                    let file_path = path.join(file.file_name());
+                                    progress_callback.on_event(&match file.error() {
+                                        Some(error) => ProgressEvent::FileFailed {
+                                            import_id,
+                                            file_path,
+                                            error: error.clone(),
+                                        },
+                                        None => ProgressEvent::FileVerified {
+                                            import_id,
+                                            file_path,
+                                        },
+                                    });
+                                }
+                                report
+                            })
+                    }),
+                ),
+            });
+
+        into_future_trait(f)
     }
 
-    /// Complete an upload to the upload service
-    pub fn complete_upload(
+    #[allow(clippy::too_many_arguments)]
+    /// Resume an upload cancelled by `upload_package_with_cancellation`,
+    /// sending only the chunks the upload service is still missing.
+    ///
+    /// If the report does not list any incomplete files, it is returned
+    /// as is.
+    pub fn resume_upload<P, C>(
         &self,
         organization_id: &OrganizationId,
-        import_id: &ImportId,
         dataset_id: &DatasetNodeId,
+        path: &P,
+        package: &model::PackagePreview,
+        report: UploadReport,
         destination_id: Option<&PackageId>,
         append: bool,
-    ) -> Future<response::Manifests> {
-        let mut params = params!(
-            "datasetId" => dataset_id,
-            "append" => if append { "true" } else { "false" }
-        );
-        if let Some(dest_id) = destination_id {
-            params.push(param!("destinationId", dest_id.clone()));
+        progress_callback: C,
+        parallelism: usize,
+        cancellation: CancellationToken,
+    ) -> Future<UploadReport>
+    where
+        P: 'static + AsRef<Path> + Send,
+        C: 'static + ProgressCallback + Clone,
+    {
+        if report.import_id() != package.import_id() {
+            return into_future_trait(future::err(Error::invalid_arguments(format!(
+                "report for import {} does not belong to package import {}",
+                report.import_id(),
+                package.import_id()
+            ))));
         }
 
-        post!(
-            self,
-            route!(
-                "/upload/complete/organizations/{organization_id}/id/{import_id}",
-                organization_id,
-                import_id
-            ),
-            params
+        if !report.is_cancelled() {
+            return into_future_trait(future::ok(report));
+        }
+
+        self.upload_package_with_cancellation(
+            organization_id,
+            dataset_id,
+            path,
+            package,
+            destination_id,
+            append,
+            progress_callback,
+            parallelism,
+            cancellation,
         )
     }
 
-    /// Get the upload status using the upload service
-    pub fn get_upload_status(
+    /// Abort an import on the upload service, discarding every chunk
+    /// received so far.
+    pub fn abort_upload(
         &self,
         organization_id: &OrganizationId,
         import_id: &ImportId,
-    ) -> Future<Option<response::FilesMissingParts>> {
-        get!(
+    ) -> Future<()> {
+        let f = delete!(
             self,
             route!(
-                "/upload/status/organizations/{organization_id}/id/{import_id}",
+                "/upload/organizations/{organization_id}/id/{import_id}",
                 organization_id,
                 import_id
             )
         )
+        .map(|_: Nothing| ());
+        into_future_trait(f)
     }
 
-    /// Get the hash of an uploaded file from the upload service
-    pub fn get_upload_hash<S>(
+    /// Build the report of a cancelled upload from the parts the upload
+    /// service is still missing, aborting the import if requested. The
+    /// files of an aborted import are reported as failed.
+    fn cancelled_upload_report(
         &self,
+        organization_id: &OrganizationId,
         import_id: &ImportId,
-        file_name: S,
-    ) -> Future<response::FileHash>
-    where
-        S: Into<String>,
-    {
-        get!(
-            self,
-            route!("/upload/hash/id/{import_id}", import_id),
-            params!("fileName" => file_name)
-        )
+        files: &[model::S3File],
+        abort: bool,
+    ) -> Future<UploadReport> {
+        let bf = self.clone();
+        let organization_id = organization_id.clone();
+        let import_id = import_id.clone();
+        // The upload service reports missing parts by file name, while
+        // files are reported under their path relative to the upload:
+        let file_names: Vec<(String, String)> = files
+            .iter()
+            .map(|f| (f.file_name().clone(), f.relative_path()))
+            .collect();
+
+        let f = self
+            .get_upload_status(&organization_id, &import_id)
+            .and_then(move |missing_parts| {
+                let incomplete: Vec<String> = missing_parts
+                    .map(|mp| mp.files.into_iter().map(|f| f.file_name).collect())
+                    .unwrap_or_default();
+                let files = file_names
+                    .into_iter()
+                    .map(|(file_name, relative_path)| {
+                        let status = if abort {
+                            FileStatus::Failed(ErrorKind::Cancelled)
+                        } else if incomplete.contains(&file_name) {
+                            FileStatus::Cancelled
+                        } else {
+                            FileStatus::Uploaded
+                        };
+                        FileReport::new(relative_path, status)
+                    })
+                    .collect();
+                let report = UploadReport::new(import_id.clone(), files);
+
+                if abort {
+                    into_future_trait(
+                        bf.abort_upload(&organization_id, &import_id)
+                            .map(move |_| report),
+                    )
+                } else {
+                    into_future_trait(future::ok(report))
+                }
+            });
+
+        into_future_trait(f)
     }
 
-    pub fn upload_file_chunks_with_retries<P, C>(
+    /// Get the packages directly contained in the collection found by
+    /// following `path` from the destination, which is either a
+    /// collection or the top level of the dataset.
+    ///
+    /// Returns the ID of the collection (`None` for the top level of the
+    /// dataset) along with its children, or `None` if some collection on
+    /// the path does not exist.
+    fn get_children_at_path(
+        &self,
+        dataset_id: &DatasetNodeId,
+        destination_id: Option<&PackageId>,
+        path: Vec<String>,
+    ) -> Future<CollectionListing> {
+        let bf = self.clone();
+
+        let start: Future<(Option<PackageId>, Vec<response::Package>)> = match destination_id {
+            Some(id) => {
+                let id = id.clone();
+                into_future_trait(self.get_package_by_id(id.clone()).map(move |package| {
+                    (Some(id), package.children().cloned().unwrap_or_default())
+                }))
+            }
+            None => into_future_trait(
+                self.get_dataset_by_id(dataset_id.clone())
+                    .map(|dataset| (None, dataset.children().cloned().unwrap_or_default())),
+            ),
+        };
+
+        let f = start.and_then(move |(parent, children)| {
+            future::loop_fn(
+                (Some((parent, children)), path.into_iter()),
+                move |(level, mut path)| match (level, path.next()) {
+                    (Some((_, children)), Some(name)) => {
+                        let collection = children
+                            .into_iter()
+                            .find(|child| child.name() == &name && child.is_collection());
+                        match collection {
+                            Some(collection) => {
+                                let id = collection.id().clone();
+                                into_future_trait(bf.get_package_by_id(id.clone()).map(
+                                    move |package| {
+                                        let children =
+                                            package.children().cloned().unwrap_or_default();
+                                        future::Loop::Continue((Some((Some(id), children)), path))
+                                    },
+                                ))
+                            }
+                            None => into_future_trait(future::ok(future::Loop::Break(None))),
+                        }
+                    }
+                    (level, _) => into_future_trait(future::ok(future::Loop::Break(level))),
+                },
+            )
+        });
+
+        into_future_trait(f)
+    }
+
+    /// Check the packages of an upload preview against the packages
+    /// already present in the upload destination, deciding what to do
+    /// with each conflicting package according to `policy`.
+    ///
+    /// Nothing is sent to the upload service; the resulting plan lists
+    /// the packages that would be skipped. With
+    /// `ConflictPolicy::SkipIdentical`, the previewed files are read from
+    /// `path` to compare their hashes against the existing source files.
+    pub fn plan_upload<P: AsRef<Path>>(
+        &self,
+        dataset_id: &DatasetNodeId,
+        destination_id: Option<&PackageId>,
+        path: P,
+        preview: response::UploadPreview,
+        policy: model::ConflictPolicy,
+    ) -> Future<model::UploadPlan> {
+        let path = path.as_ref().to_path_buf();
+        let mut paths: Vec<Vec<String>> = preview
+            .iter()
+            .map(|package| package.preview_path_components())
+            .collect();
+        paths.sort();
+        paths.dedup();
+
+        let bf = self.clone();
+        let listings = future::join_all(
+            paths
+                .into_iter()
+                .map(|path| {
+                    self.get_children_at_path(dataset_id, destination_id, path.clone())
+                        .map(move |level| (path, level))
+                })
+                .collect::<Vec<_>>(),
+        );
+
+        let f = listings
+            .map(|listings| {
+                listings
+                    .into_iter()
+                    .collect::<HashMap<Vec<String>, CollectionListing>>()
+            })
+            .and_then(move |listings| {
+                let planned = preview.into_iter().map(move |package| {
+                    let level = listings
+                        .get(&package.preview_path_components())
+                        .cloned()
+                        .unwrap_or(None);
+                    let (parent, children) = level.unwrap_or((None, vec![]));
+
+                    let conflicts: Vec<PackageId> = children
+                        .iter()
+                        .filter(|child| child.name() == package.package_name())
+                        .map(|child| child.id().clone())
+                        .collect();
+
+                    if conflicts.is_empty() {
+                        return into_future_trait(future::ok(model::PlannedPackage::new(
+                            package,
+                            model::ConflictAction::Upload,
+                            parent,
+                            conflicts,
+                        )));
+                    }
+
+                    match policy {
+                        model::ConflictPolicy::SkipIdentical => {
+                            let sources = conflicts
+                                .iter()
+                                .map(|id| bf.get_package_sources(id.clone()))
+                                .collect::<Vec<_>>();
+                            let path = path.clone();
+                            into_future_trait(future::join_all(sources).map(move |sources| {
+                                let local_hash = |file: &model::S3File, chunk_size| {
+                                    FileChecksums::default()
+                                        .file_hash(
+                                            &file.relative_path(),
+                                            path.join(file.file_name()),
+                                            chunk_size,
+                                        )
+                                        .ok()
+                                        .map(|hash| hash.0)
+                                };
+                                let identical = sources.into_iter().any(|files| {
+                                    package.matches_sources(&files.take(), local_hash)
+                                });
+                                let action = if identical {
+                                    model::ConflictAction::Skip
+                                } else {
+                                    model::ConflictAction::Upload
+                                };
+                                model::PlannedPackage::new(package, action, parent, conflicts)
+                            }))
+                        }
+                        model::ConflictPolicy::Rename => {
+                            let taken: Vec<&String> =
+                                children.iter().map(|child| child.name()).collect();
+                            let name = model::unique_package_name(package.package_name(), &taken);
+                            into_future_trait(future::ok(model::PlannedPackage::new(
+                                package,
+                                model::ConflictAction::Rename(name),
+                                parent,
+                                conflicts,
+                            )))
+                        }
+                        model::ConflictPolicy::Replace => {
+                            into_future_trait(future::ok(model::PlannedPackage::new(
+                                package,
+                                model::ConflictAction::Replace,
+                                parent,
+                                conflicts,
+                            )))
+                        }
+                        model::ConflictPolicy::Append => {
+                            into_future_trait(future::ok(model::PlannedPackage::new(
+                                package,
+                                model::ConflictAction::Append,
+                                parent,
+                                conflicts,
+                            )))
+                        }
+                    }
+                });
+                future::join_all(planned)
+            })
+            .map(model::UploadPlan::new);
+
+        into_future_trait(f)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    /// Carry out the action planned for a single package by `plan_upload`.
+    ///
+    /// Skipped packages are reported without sending anything. Replaced
+    /// packages are deleted only once the new package was uploaded and
+    /// verified, and never recursively. Renamed packages are renamed once
+    /// the import has completed.
+    pub fn upload_planned_package<P, C>(
         &self,
         organization_id: &OrganizationId,
-        import_id: &ImportId,
+        dataset_id: &DatasetNodeId,
         path: &P,
-        files: Vec<model::S3File>,
+        planned: &model::PlannedPackage,
+        destination_id: Option<&PackageId>,
         progress_callback: C,
         parallelism: usize,
-    ) -> Stream<ImportId>
+    ) -> Future<UploadReport>
     where
         P: 'static + AsRef<Path> + Send,
         C: 'static + ProgressCallback + Clone,
     {
-        #[derive(Clone)]
-        struct LoopDependencies<C: ProgressCallback + Clone> {
-            organization_id: OrganizationId,
-            import_id: ImportId,
-            path: PathBuf,
-            files: Vec<model::S3File>,
-            missing_parts: Option<response::FilesMissingParts>,
-            result: Option<Vec<ImportId>>,
-            progress_callback: C,
-            try_num: usize,
-            bf: Blackfynn,
-            parallelism: usize,
-        }
+        let preview = planned.preview().clone();
+        let conflicts = planned.conflicts().clone();
 
-        impl<C: ProgressCallback + Clone> LoopDependencies<C> {
-            pub fn increment_attempt_count(self) -> Self {
-                Self {
-                    organization_id: self.organization_id,
-                    import_id: self.import_id,
-                    path: self.path,
-                    files: self.files,
-                    missing_parts: self.missing_parts,
-                    result: self.result,
-                    progress_callback: self.progress_callback,
-                    try_num: self.try_num + 1,
-                    bf: self.bf,
-                    parallelism: self.parallelism,
-                }
+        match planned.action().clone() {
+            model::ConflictAction::Skip => {
+                let files = preview
+                    .files()
+                    .iter()
+                    .map(|file| FileReport::new(file.relative_path(), FileStatus::Skipped))
+                    .collect();
+                into_future_trait(future::ok(UploadReport::new(
+                    preview.import_id().clone(),
+                    files,
+                )))
+            }
+            model::ConflictAction::Upload => self.upload_package(
+                organization_id,
+                dataset_id,
+                path,
+                &preview,
+                destination_id,
+                false,
+                progress_callback,
+                parallelism,
+            ),
+            model::ConflictAction::Append => self.upload_package(
+                organization_id,
+                dataset_id,
+                path,
+                &preview,
+                conflicts.first(),
+                true,
+                progress_callback,
+                parallelism,
+            ),
+            model::ConflictAction::Replace => {
+                let bf = self.clone();
+                let organization_id = organization_id.clone();
+                let dataset_id = dataset_id.clone();
+                let destination_id = destination_id.cloned();
+                let path = path.as_ref().to_path_buf();
+
+                // The existing packages are only deleted once the new package
+                // is in place, and never recursively, so a failed upload or
+                // a conflicting collection loses nothing:
+                let f = self
+                    .upload_package(
+                        &organization_id,
+                        &dataset_id,
+                        &path,
+                        &preview,
+                        destination_id.as_ref(),
+                        false,
+                        progress_callback,
+                        parallelism,
+                    )
+                    .and_then(move |report| {
+                        if !report.is_success() {
+                            return into_future_trait(future::ok(report));
+                        }
+                        let f = bf
+                            .delete_packages_with_options(conflicts, false, false)
+                            .and_then(move |response| match response.failures().first() {
+                                Some(failure) => Err(Error::upload_error(format!(
+                                    "uploaded package, but could not remove the package it \
+                                     replaces {}: {}",
+                                    failure.id(),
+                                    failure.error()
+                                ))),
+                                None => Ok(report),
+                            });
+                        into_future_trait(f)
+                    });
+                into_future_trait(f)
+            }
+            model::ConflictAction::Rename(new_name) => {
+                let bf = self.clone();
+                let package_name = preview.package_name().clone();
+
+                // The package is renamed through the ID reported when the
+                // import completed:
+                let f = self
+                    .upload_package(
+                        organization_id,
+                        dataset_id,
+                        path,
+                        &preview,
+                        destination_id,
+                        false,
+                        progress_callback,
+                        parallelism,
+                    )
+                    .and_then(move |report| match report.package_id().cloned() {
+                        Some(package_id) => into_future_trait(
+                            bf.update_package(package_id, new_name).map(|_| report),
+                        ),
+                        None if !report.is_success() => into_future_trait(future::ok(report)),
+                        None => into_future_trait(future::err(Error::upload_error(format!(
+                            "the platform did not report the package {} was imported into",
+                            package_name
+                        )))),
+                    });
+                into_future_trait(f)
             }
         }
+    }
+}
 
-        let ld = LoopDependencies {
-            organization_id: organization_id.clone(),
-            import_id: import_id.clone(),
-            path: path.as_ref().to_path_buf(),
-            files,
-            missing_parts: None,
-            result: None,
-            progress_callback,
-            try_num: 0,
-            bf: self.clone(),
-            parallelism,
-        };
+// Tests that need neither credentials nor the network:
+#[cfg(test)]
+mod offline_tests {
+    use super::*;
 
-        let retry_loop = future::loop_fn(ld, |mut ld| {
+    fn package(id: &str, package_type: &str, children: serde_json::Value) -> response::Package {
+        serde_json::from_value(serde_json::json!({
+            "content": {
+                "id": id,
+                "name": id,
+                "datasetId": "N:dataset:1",
+                "packageType": package_type,
+                "createdAt": "2018-01-01T00:00:00Z",
+                "updatedAt": "2018-01-01T00:00:00Z"
+            },
+            "children": children
+        }))
+        .unwrap()
+    }
 
-            let ld_err = ld.clone();
+    #[test]
+    fn path_components_skip_empty_names() {
+        assert_eq!(
+            path_components("/a//b/file.edf"),
+            vec!["a", "b", "file.edf"]
+        );
+        assert!(path_components("/").is_empty());
+    }
 
-            ld.bf
-                .get_upload_status(&ld.organization_id, &ld.import_id)
-                .map(|parts| {
-                    ld.missing_parts = parts;
-                    ld
-                })
-                .and_then(|ld| {
-                    ld.bf
-                        .upload_file_chunks(
-                            &ld.organization_id,
-                            &ld.import_id,
-                            ld.path.clone(),
-                            ld.files.clone(),
-                            ld.missing_parts.clone(),
-                            ld.progress_callback.clone(),
-                            ld.parallelism,
-                        )
-                        .collect()
-                        .map(future::Loop::Break)
-                })
-                .into_future()
-                .or_else(move |err| {
+    #[test]
+    fn time_ranges_are_split_into_pages() {
+        let pages = |start, end, page_duration| {
+            iter::successors(
+                next_timeseries_page(start, end, page_duration),
+                |&(_, page_end)| next_timeseries_page(page_end, end, page_duration),
+            )
+            .collect::<Vec<_>>()
+        };
+        assert_eq!(pages(0, 25, 10), vec![(0, 10), (10, 20), (20, 25)]);
+        assert_eq!(pages(0, 10, 10), vec![(0, 10)]);
+        assert!(pages(10, 10, 10).is_empty());
+        assert_eq!(pages(0, i64::MAX, i64::MAX), vec![(0, i64::MAX)]);
+    }
+
+    #[test]
+    fn timeseries_pages_must_last_a_positive_duration() {
+        let bf = Blackfynn::new(Config::new(Environment::NonProduction));
+        let err = bf
+            .get_timeseries_with_page_duration(PackageId::new("N:package:1"), vec![], 0, 10, 0)
+            .collect()
+            .wait()
+            .unwrap_err();
+        assert_eq!(
+            err.kind(),
+            &ErrorKind::InvalidArguments {
+                message: "the duration of a page (0) must be positive".to_string()
+            }
+        );
+    }
 
-                    debug!("Upload encountered an error: {error}", error = err);
+    #[test]
+    fn only_empty_collections_are_deleted_unless_recursive() {
+        let leaf = package("N:package:2", "CSV", serde_json::json!([]));
+        let collection = package(
+            "N:collection:1",
+            "Collection",
+            serde_json::json!([{
+                "content": {
+                    "id": "N:package:2",
+                    "name": "N:package:2",
+                    "datasetId": "N:dataset:1",
+                    "packageType": "CSV",
+                    "createdAt": "2018-01-01T00:00:00Z",
+                    "updatedAt": "2018-01-01T00:00:00Z"
+                }
+            }]),
+        );
+        let empty = package("N:collection:3", "Collection", serde_json::json!([]));
+        let id = |id: &str| PackageId::new(id);
 
-                    match err.kind() {
-                        // error cannot be retried, bubble up the error
-                        ErrorKind::ApiError{ status_code, .. } if NONRETRYABLE_STATUS_CODES.contains(status_code) => {
-                            debug!("Upload received status {status} from API which cannot be retried", status = status_code);
-                            into_future_trait(future::err(err))
-                        }
+        let failure = deletable(id("N:collection:1"), Ok(collection.clone()), false).unwrap_err();
+        assert_eq!(failure.id(), "N:collection:1");
+        assert_eq!(failure.error(), "collection is not empty");
 
-                        // error that should be retried (if we are under MAX_RETRIES), retry the upload
-                        _ if MAX_RETRIES > ld_err.try_num => {
-                            let delay = retry_delay(ld_err.try_num);
+        assert!(deletable(id("N:collection:1"), Ok(collection), true).is_ok());
+        assert!(deletable(id("N:collection:3"), Ok(empty), false).is_ok());
+        assert!(deletable(id("N:package:2"), Ok(leaf), false).is_ok());
 
-                            debug!("Waiting {millis} millis to retry...", millis = delay);
+        let failure = deletable(id("N:package:4"), Err(Error::cancelled()), false).unwrap_err();
+        assert_eq!(failure.error(), "operation cancelled");
+    }
 
-                            // delay
-                            let deadline = time::Instant::now() + time::Duration::from_millis(delay);
-                            let continue_loop = tokio::timer::Delay::new(deadline)
-                                .map_err(Into::<Error>::into)
-                                .map(move |_| {
-                                    debug!(
-                                        "Attempting to resume missing parts. Attempt {try_num}/{retries})...",
-                                        try_num = ld_err.try_num, retries = MAX_RETRIES
-                                    );
-                                    future::Loop::Continue(ld_err.increment_attempt_count())
-                                });
-                            into_future_trait(continue_loop)
-                        }
+    #[test]
+    fn dry_runs_list_the_contents_of_collections() {
+        let bf = Blackfynn::new(Config::new(Environment::NonProduction));
+        let collection = package(
+            "N:collection:1",
+            "Collection",
+            serde_json::json!([
+                {"content": {
+                    "id": "N:package:2",
+                    "name": "a.csv",
+                    "datasetId": "N:dataset:1",
+                    "packageType": "CSV",
+                    "createdAt": "2018-01-01T00:00:00Z",
+                    "updatedAt": "2018-01-01T00:00:00Z"
+                }},
+                {"content": {
+                    "id": "N:package:3",
+                    "name": "b.csv",
+                    "datasetId": "N:dataset:1",
+                    "packageType": "CSV",
+                    "createdAt": "2018-01-01T00:00:00Z",
+                    "updatedAt": "2018-01-01T00:00:00Z"
+                }}
+            ]),
+        );
 
-                        // MAX_RETRIES exceeded, bubble up the error
-                        _ => {
-                            error!("Retries exceeded during upload. Bubbling up error {error}", error = err);
-                            into_future_trait(future::err(err))
-                        }
-                    }
-                })
-        })
-        .map(|import_ids| {
-            future::ok::<Stream<ImportId>, Error>(
-                into_stream_trait(stream::futures_unordered(
-                    import_ids
-                        .iter()
-                        .map(|import_id| future::ok(import_id.clone())),
-                )),
+        // Packages that are not collections are listed without a request:
+        let ids = bf.get_descendant_ids(&collection).wait().unwrap();
+        assert_eq!(
+            ids,
+            vec![PackageId::new("N:package:2"), PackageId::new("N:package:3")]
+        );
+    }
+
+    #[test]
+    fn nested_files_are_tracked_once_by_the_aggregator() {
+        let path = PathBuf::from(concat!(env!("CARGO_MANIFEST_DIR"), "/test/data/small"));
+        let size = fs::metadata(path.join("example.csv")).unwrap().len();
+        // Two files of the same name, in different directories:
+        let preview: response::UploadPreview = serde_json::from_value(serde_json::json!({
+            "packages": [{
+                "packageName": "example",
+                "importId": "import id",
+                "files": [
+                    {"fileName": "example.csv", "size": size, "filePath": ["a"]},
+                    {"fileName": "example.csv", "size": size, "filePath": ["a", "b"]}
+                ],
+                "groupSize": 2
+            }]
+        }))
+        .unwrap();
+
+        let aggregator = progress::ProgressAggregator::new();
+        aggregator.expect_preview(&path, &preview);
+
+        let import_id = ImportId::new("import id");
+        for file in preview.packages()[0].files() {
+            aggregator.on_event(&ProgressEvent::FileStarted {
+                import_id: import_id.clone(),
+                file_path: progress_path(&path, file),
+                size: file.size(),
+            });
+            let payload = file_payload(&import_id, &path, file, None, FileChecksums::default());
+            for chunk in payload.wait() {
+                let (_, update) = chunk.unwrap();
+                aggregator.on_event(&ProgressEvent::ChunkSent(update));
+            }
+            let report = FileReport::new(file.relative_path(), FileStatus::Verified);
+            aggregator.on_event(&ProgressEvent::FileVerified {
+                import_id: import_id.clone(),
+                file_path: path.join(report.file_name()),
+            });
+        }
+
+        let summary = aggregator.summary();
+        assert_eq!(summary.files_total(), 2);
+        assert_eq!(summary.files_verified(), 2);
+        assert_eq!(summary.bytes_sent(), 2 * size);
+        assert_eq!(summary.bytes_total(), 2 * size);
+    }
+
+    #[test]
+    fn skipped_files_are_reported_by_relative_path() {
+        let bf = Blackfynn::new(Config::new(Environment::NonProduction));
+        let preview: response::UploadPreview = serde_json::from_value(serde_json::json!({
+            "packages": [{
+                "packageName": "example",
+                "importId": "import id",
+                "files": [
+                    {"fileName": "example.csv", "size": 1, "filePath": ["a"]},
+                    {"fileName": "example.csv", "size": 1, "filePath": ["a", "b"]}
+                ],
+                "groupSize": 2
+            }]
+        }))
+        .unwrap();
+        let planned = model::PlannedPackage::new(
+            preview.packages()[0].clone(),
+            model::ConflictAction::Skip,
+            None,
+            vec![],
+        );
+
+        let report = bf
+            .upload_planned_package(
+                &OrganizationId::new("N:organization:1"),
+                &DatasetNodeId::new("N:dataset:1"),
+                &PathBuf::from("."),
+                &planned,
+                None,
+                progress::NoProgress,
+                1,
             )
-                .into_stream()
-            .flatten()
-        })
-        .into_stream()
-        .flatten();
+            .wait()
+            .unwrap();
+        let names: Vec<&String> = report.files().iter().map(|f| f.file_name()).collect();
+        assert_eq!(names, vec!["a/example.csv", "a/b/example.csv"]);
+    }
 
-        into_stream_trait(retry_loop)
+    #[test]
+    fn organization_admin_is_required() {
+        let organization = |is_admin| -> response::Organization {
+            serde_json::from_value(serde_json::json!({
+                "isAdmin": is_admin,
+                "isOwner": false,
+                "owners": [],
+                "administrators": [],
+                "organization": {
+                    "id": "N:organization:1",
+                    "name": "Organization",
+                    "slug": "organization",
+                    "encryptionKeyId": "key"
+                }
+            }))
+            .unwrap()
+        };
+
+        assert!(require_admin(&organization(true)).is_ok());
+        match require_admin(&organization(false)).unwrap_err().kind() {
+            ErrorKind::Forbidden { message } => assert!(message.contains("N:organization:1")),
+            kind => panic!("unexpected error: {:?}", kind),
+        }
     }
 }
 
@@ -1648,13 +5417,13 @@ pub mod tests {
 
         assert!(collaborators.iter().all(|c| c.role().is_some()));
 
-        let mut collaborators: Vec<(String, String)> = collaborators
+        let mut collaborators: Vec<(String, Role)> = collaborators
             .iter()
             .map(|u| (u.first_name().clone(), u.role().unwrap().clone()))
             .collect();
         collaborators.sort();
 
-        let expected = ("Michael".to_string(), "owner".to_string());
+        let expected = ("Michael".to_string(), Role::Owner);
 
         assert!(collaborators.contains(&expected));
     }
@@ -1669,13 +5438,13 @@ pub mod tests {
         .unwrap();
         assert!(collaborators.iter().all(|c| c.role().is_some()));
 
-        let mut collaborators: Vec<(String, String)> = collaborators
+        let mut collaborators: Vec<(String, Role)> = collaborators
             .iter()
             .map(|t| (t.name().clone(), t.role().unwrap().clone()))
             .collect();
         collaborators.sort();
 
-        let expected = vec![("Agent Devs".to_string(), "manager".to_string())];
+        let expected = vec![("Agent Devs".to_string(), Role::Manager)];
 
         assert_eq!(collaborators, expected);
     }
@@ -1698,6 +5467,196 @@ pub mod tests {
         assert_eq!(organization_role, expected);
     }
 
+    #[test]
+    fn share_then_unshare_dataset_with_team() {
+        let result = run(&bf(), move |bf| {
+            into_future_trait(
+                bf.login(TEST_API_KEY, TEST_SECRET_KEY)
+                    .and_then(move |_| bf.get_teams().map(|teams| (bf, teams)))
+                    .and_then(move |(bf, teams)| {
+                        let team_id = teams[0].clone().take().id().clone();
+                        bf.create_dataset(
+                            rand_suffix("$agent-test-dataset".to_string()),
+                            Some("A test dataset created by the agent".to_string()),
+                        )
+                        .map(move |ds| (bf, ds.id().clone(), team_id))
+                    })
+                    .and_then(move |(bf, ds_id, team_id)| {
+                        bf.add_dataset_team_collaborators(
+                            ds_id.clone(),
+                            vec![team_id.clone()],
+                            Role::Editor,
+                        )
+                        .map(move |changes| {
+                            assert!(changes.change(&*team_id).unwrap().success());
+                            (bf, ds_id, team_id)
+                        })
+                    })
+                    .and_then(move |(bf, ds_id, team_id)| {
+                        bf.get_dataset_team_collaborators(ds_id.clone())
+                            .map(move |teams| {
+                                let team = teams.iter().find(|t| t.id() == &team_id).unwrap();
+                                assert_eq!(team.role(), Some(&Role::Editor));
+                                (bf, ds_id, team_id)
+                            })
+                    })
+                    .and_then(move |(bf, ds_id, team_id)| {
+                        bf.remove_dataset_team_collaborators(ds_id.clone(), vec![team_id])
+                            .map(|changes| {
+                                assert_eq!(changes.counts().teams(), 0);
+                                (bf, ds_id)
+                            })
+                    })
+                    .and_then(move |(bf, ds_id)| bf.delete_dataset(ds_id)),
+            )
+        });
+
+        if let Err(err) = result {
+            panic!("{}", err);
+        }
+    }
+
+    #[test]
+    fn my_dataset_role_is_owner_of_created_dataset() {
+        let result = run(&bf(), move |bf| {
+            into_future_trait(
+                bf.login(TEST_API_KEY, TEST_SECRET_KEY)
+                    .and_then(move |_| {
+                        bf.create_dataset(
+                            rand_suffix("$agent-test-dataset".to_string()),
+                            Some("A test dataset created by the agent".to_string()),
+                        )
+                        .map(|ds| (bf, ds.id().clone()))
+                    })
+                    .and_then(move |(bf, ds_id)| {
+                        bf.my_dataset_role(ds_id.clone()).map(|role| {
+                            assert_eq!(role, Some(Role::Owner));
+                            assert!(role.unwrap() >= Role::Editor);
+                            (bf, ds_id)
+                        })
+                    })
+                    .and_then(move |(bf, ds_id)| bf.delete_dataset(ds_id)),
+            )
+        });
+
+        if let Err(err) = result {
+            panic!("{}", err);
+        }
+    }
+
+    #[test]
+    fn create_then_rename_then_delete_team() {
+        let result = run(&bf(), move |bf| {
+            into_future_trait(
+                bf.login(TEST_API_KEY, TEST_SECRET_KEY)
+                    .and_then(move |_| {
+                        bf.create_team(
+                            OrganizationId::new(FIXTURE_ORGANIZATION),
+                            rand_suffix("$agent-test-team".to_string()),
+                        )
+                        .map(|team| (bf, team.take().id().clone()))
+                    })
+                    .and_then(move |(bf, team_id)| {
+                        bf.rename_team(
+                            OrganizationId::new(FIXTURE_ORGANIZATION),
+                            team_id.clone(),
+                            "$agent-test-team-renamed",
+                        )
+                        .map(|team| {
+                            assert_eq!(team.take().name(), "$agent-test-team-renamed");
+                            (bf, team_id)
+                        })
+                    })
+                    .and_then(move |(bf, team_id)| {
+                        bf.add_team_members_by_email(
+                            OrganizationId::new(FIXTURE_ORGANIZATION),
+                            team_id.clone(),
+                            vec![FIXTURE_EMAIL],
+                        )
+                        .map(|_| (bf, team_id))
+                    })
+                    .and_then(move |(bf, team_id)| {
+                        bf.get_team_members(
+                            OrganizationId::new(FIXTURE_ORGANIZATION),
+                            team_id.clone(),
+                        )
+                        .map(|members| {
+                            assert!(members.iter().any(|m| m.email() == FIXTURE_EMAIL));
+                            (bf, team_id)
+                        })
+                    })
+                    .and_then(move |(bf, team_id)| {
+                        bf.remove_team_member_by_email(
+                            OrganizationId::new(FIXTURE_ORGANIZATION),
+                            team_id.clone(),
+                            FIXTURE_EMAIL,
+                        )
+                        .map(|_| (bf, team_id))
+                    })
+                    .and_then(move |(bf, team_id)| {
+                        bf.delete_team(OrganizationId::new(FIXTURE_ORGANIZATION), team_id)
+                    }),
+            )
+        });
+
+        if let Err(err) = result {
+            panic!("{}", err);
+        }
+    }
+
+    #[test]
+    fn fetch_user_by_email_then_id() {
+        let result = run(&bf(), move |bf| {
+            into_future_trait(
+                bf.login(TEST_API_KEY, TEST_SECRET_KEY)
+                    .and_then(move |_| {
+                        bf.get_user_by_email(
+                            OrganizationId::new(FIXTURE_ORGANIZATION),
+                            FIXTURE_EMAIL,
+                        )
+                        .map(|user| (bf, user))
+                    })
+                    .and_then(move |(bf, user)| {
+                        bf.get_user_by_id(
+                            OrganizationId::new(FIXTURE_ORGANIZATION),
+                            user.id().clone(),
+                        )
+                        .map(move |other| assert_eq!(user, other))
+                    }),
+            )
+        });
+
+        if let Err(err) = result {
+            panic!("{}", err);
+        }
+    }
+
+    #[test]
+    fn create_then_list_then_revoke_api_key() {
+        let result = run(&bf(), move |bf| {
+            into_future_trait(
+                bf.login(TEST_API_KEY, TEST_SECRET_KEY)
+                    .and_then(move |_| {
+                        bf.create_api_key(rand_suffix("$agent-test-key".to_string()))
+                            .map(|api_key| (bf, api_key))
+                    })
+                    .and_then(move |(bf, api_key)| {
+                        assert!(api_key.secret().is_some());
+                        bf.list_api_keys().map(move |keys| {
+                            assert!(keys.iter().any(|k| k.key() == api_key.key()));
+                            assert!(keys.iter().all(|k| k.secret().is_none()));
+                            (bf, api_key.key().clone())
+                        })
+                    })
+                    .and_then(move |(bf, key)| bf.revoke_api_key(key)),
+            )
+        });
+
+        if let Err(err) = result {
+            panic!("{}", err);
+        }
+    }
+
     #[test]
     fn fetch_members() {
         let members = run(&bf(), move |bf| {
@@ -2031,6 +5990,133 @@ pub mod tests {
         }
     }
 
+    #[test]
+    fn delete_collection_only_when_recursive() {
+        let result = run(&bf(), move |bf| {
+            into_future_trait(
+                bf.login(TEST_API_KEY, TEST_SECRET_KEY)
+                    .and_then(move |_| {
+                        bf.create_dataset(
+                            rand_suffix("$agent-test-dataset".to_string()),
+                            Some("A test dataset created by the agent".to_string()),
+                        )
+                        .map(|ds| (bf, ds))
+                    })
+                    .and_then(move |(bf, ds)| Ok(ds.id().clone()).map(|id| (bf, id)))
+                    .and_then(move |(bf, ds_id)| {
+                        bf.create_package(
+                            rand_suffix("$agent-test-collection"),
+                            "Collection",
+                            ds_id.clone(),
+                            None as Option<String>,
+                        )
+                        .map(|col| (bf, ds_id, col))
+                    })
+                    .and_then(move |(bf, ds_id, col)| {
+                        let col_id = col.id().clone();
+                        bf.create_package(
+                            rand_suffix("$agent-test-package"),
+                            "Text",
+                            ds_id.clone(),
+                            Some(col_id.clone()),
+                        )
+                        .map(|pkg| (bf, ds_id, col_id, pkg.id().clone()))
+                    })
+                    .and_then(move |(bf, ds_id, col_id, pkg_id)| {
+                        // A non-empty collection is not deleted by default
+                        bf.delete_packages(vec![col_id.clone()])
+                            .and_then(move |response| {
+                                assert!(response.success().is_empty());
+                                assert_eq!(response.failures().len(), 1);
+                                assert_eq!(response.failures()[0].id(), col_id.borrow() as &str);
+                                Ok((bf, ds_id, col_id, pkg_id))
+                            })
+                    })
+                    .and_then(move |(bf, ds_id, col_id, pkg_id)| {
+                        // A dry run lists the collection and its contents
+                        let expected: Vec<String> = vec![col_id.clone().into(), pkg_id.into()];
+                        bf.delete_packages_with_options(vec![col_id.clone()], true, true)
+                            .and_then(move |response| {
+                                assert_eq!(response.success(), &expected);
+                                assert!(response.failures().is_empty());
+                                Ok((bf, ds_id, col_id))
+                            })
+                    })
+                    .and_then(move |(bf, ds_id, col_id)| {
+                        bf.get_dataset_by_id(ds_id.clone()).and_then(|dataset| {
+                            // Nothing was deleted by the dry run
+                            assert_eq!(dataset.children().unwrap().len(), 1);
+                            Ok((bf, ds_id, col_id))
+                        })
+                    })
+                    .and_then(move |(bf, ds_id, col_id)| {
+                        bf.delete_packages_with_options(vec![col_id], true, false)
+                            .and_then(|response| {
+                                assert_eq!(response.success().len(), 1);
+                                assert!(response.failures().is_empty());
+                                Ok((bf, ds_id))
+                            })
+                    })
+                    .and_then(move |(bf, ds_id)| bf.delete_dataset(ds_id)),
+            )
+        });
+
+        if let Err(err) = result {
+            panic!("{}", err);
+        }
+    }
+
+    #[test]
+    fn ensure_then_resolve_then_walk_collection_path() {
+        let result = run(&bf(), move |bf| {
+            into_future_trait(
+                bf.login(TEST_API_KEY, TEST_SECRET_KEY)
+                    .and_then(move |_| {
+                        bf.create_dataset(
+                            rand_suffix("$agent-test-dataset".to_string()),
+                            Some("A test dataset created by the agent".to_string()),
+                        )
+                        .map(|ds| (bf, ds))
+                    })
+                    .and_then(move |(bf, ds)| Ok(ds.id().clone()).map(|id| (bf, id)))
+                    .and_then(move |(bf, ds_id)| {
+                        bf.ensure_collection_path(&ds_id, "a/b")
+                            .map(|leaf_id| (bf, ds_id, leaf_id))
+                    })
+                    .and_then(move |(bf, ds_id, leaf_id)| {
+                        // Existing collections are reused
+                        bf.ensure_collection_path(&ds_id, "a/b/c")
+                            .and_then(move |c_id| {
+                                bf.get_package_by_id(leaf_id.clone()).map(move |b| {
+                                    assert_eq!(b.children().unwrap().len(), 1);
+                                    assert_eq!(b.children().unwrap()[0].id(), &c_id);
+                                    (bf, ds_id, c_id)
+                                })
+                            })
+                    })
+                    .and_then(move |(bf, ds_id, c_id)| {
+                        bf.resolve_path(&ds_id, "/a/b/c/").map(move |c| {
+                            assert_eq!(c.id(), &c_id);
+                            (bf, ds_id)
+                        })
+                    })
+                    .and_then(move |(bf, ds_id)| {
+                        bf.walk(&ds_id).collect().map(move |packages| {
+                            let paths: Vec<String> =
+                                packages.into_iter().map(|(path, _)| path).collect();
+                            assert_eq!(paths, vec!["a", "a/b", "a/b/c"]);
+                            (bf, ds_id)
+                        })
+                    })
+                    .and_then(move |(bf, ds_id)| bf.delete_dataset(ds_id)),
+            )
+        });
+
+        if let Err(err) = result {
+            panic!("{}", err);
+        }
+    }
+
     fn upload_to_upload_service_with_delete(files: Vec<(UploadId, String)>) -> Result<()> {
         run(&bf(), move |bf| {
             let f = upload_to_upload_service(bf, files.clone())
@@ -2498,4 +6584,24 @@ pub mod tests {
             panic!();
         }
     }
+
+    #[test]
+    fn failed_preconditions_are_conflicts() {
+        let err = Blackfynn::response_error(StatusCode::PRECONDITION_FAILED, b"stale");
+        assert_eq!(
+            err.kind(),
+            &ErrorKind::Conflict {
+                message: "stale".to_string()
+            }
+        );
+
+        let err = Blackfynn::response_error(StatusCode::NOT_FOUND, b"missing");
+        assert_eq!(
+            err.kind(),
+            &ErrorKind::ApiError {
+                status_code: StatusCode::NOT_FOUND,
+                message: "missing".to_string()
+            }
+        );
+    }
 }
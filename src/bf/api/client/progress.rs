@@ -1,9 +1,13 @@
 // Copyright (c) 2018 Blackfynn, Inc. All Rights Reserved.
 
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
+use crate::bf::api::response::UploadPreview;
 use crate::bf::model::ImportId;
+use crate::bf::ErrorKind;
 
 /// A trait defining a progress indicator callback. Every time a file part
 /// successfully completes, `update` will be called with new, update statistics
@@ -11,6 +15,14 @@ use crate::bf::model::ImportId;
 pub trait ProgressCallback: Send + Sync {
     /// Called when an uploaded progress update occurs.
     fn on_update(&self, _: &ProgressUpdate);
+
+    /// Called for every event of an upload. By default, only the
+    /// per-chunk updates are passed along to `on_update`.
+    fn on_event(&self, event: &ProgressEvent) {
+        if let ProgressEvent::ChunkSent(update) = event {
+            self.on_update(update)
+        }
+    }
 }
 
 /// An implementation of `ProgressCallback` that does nothing.
@@ -27,19 +39,281 @@ impl ProgressCallback for Box<dyn ProgressCallback> {
     fn on_update(&self, _update: &ProgressUpdate) {
         self.as_ref().on_update(_update)
     }
+
+    fn on_event(&self, event: &ProgressEvent) {
+        self.as_ref().on_event(event)
+    }
 }
 
-impl ProgressCallback for Arc<Box<dyn ProgressCallback>> {
+impl<C: ProgressCallback + ?Sized> ProgressCallback for Arc<C> {
     fn on_update(&self, _update: &ProgressUpdate) {
-        let this = self.clone();
-        if let Ok(cb) = Arc::try_unwrap(this) {
-            cb.on_update(_update)
+        self.as_ref().on_update(_update)
+    }
+
+    fn on_event(&self, event: &ProgressEvent) {
+        self.as_ref().on_event(event)
+    }
+}
+
+/// An event occurring during an upload.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ProgressEvent {
+    /// The chunks of a file are about to be sent.
+    FileStarted {
+        import_id: ImportId,
+        file_path: PathBuf,
+        size: u64,
+    },
+    /// A chunk of a file was received by the upload service.
+    ChunkSent(ProgressUpdate),
+    /// A chunk of a file is being sent again after a failed attempt.
+    ChunkRetried {
+        import_id: ImportId,
+        file_path: PathBuf,
+        chunk_number: usize,
+        attempt: usize,
+    },
+    /// The hash of an uploaded file matches the hash computed locally.
+    FileVerified {
+        import_id: ImportId,
+        file_path: PathBuf,
+    },
+    /// A file could not be uploaded or verified.
+    FileFailed {
+        import_id: ImportId,
+        file_path: PathBuf,
+        error: ErrorKind,
+    },
+    /// The upload service has completed the import.
+    ImportCompleted { import_id: ImportId },
+}
+
+/// A snapshot of the progress of an upload across all of its files.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProgressSummary {
+    files_total: usize,
+    files_completed: usize,
+    files_verified: usize,
+    files_failed: usize,
+    chunks_retried: usize,
+    imports_completed: usize,
+    bytes_sent: u64,
+    bytes_total: u64,
+    elapsed: Duration,
+}
+
+impl ProgressSummary {
+    /// Returns the number of files expected or started so far.
+    pub fn files_total(&self) -> usize {
+        self.files_total
+    }
+
+    /// Returns the number of files with all chunks sent.
+    pub fn files_completed(&self) -> usize {
+        self.files_completed
+    }
+
+    pub fn files_verified(&self) -> usize {
+        self.files_verified
+    }
+
+    pub fn files_failed(&self) -> usize {
+        self.files_failed
+    }
+
+    pub fn chunks_retried(&self) -> usize {
+        self.chunks_retried
+    }
+
+    pub fn imports_completed(&self) -> usize {
+        self.imports_completed
+    }
+
+    /// Returns the number of bytes sent across all files.
+    pub fn bytes_sent(&self) -> u64 {
+        self.bytes_sent
+    }
+
+    /// Returns the combined size of all files expected or started so far.
+    pub fn bytes_total(&self) -> u64 {
+        self.bytes_total
+    }
+
+    /// Returns the time elapsed since the first event.
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    /// Returns the upload percentage completed across all files.
+    pub fn percent_done(&self) -> f32 {
+        if self.bytes_total == 0 {
+            if self.files_total > 0 && self.files_completed == self.files_total {
+                return 100.0;
+            } else {
+                return 0.0;
+            }
+        }
+
+        (self.bytes_sent as f32 / self.bytes_total as f32) * 100.0
+    }
+
+    /// Returns the average throughput, in bytes per second.
+    pub fn throughput(&self) -> f64 {
+        let seconds = self.elapsed.as_secs_f64();
+        if seconds > 0.0 {
+            self.bytes_sent as f64 / seconds
+        } else {
+            0.0
+        }
+    }
+
+    /// Returns the estimated time remaining at the current throughput,
+    /// if anything has been sent yet.
+    pub fn eta(&self) -> Option<Duration> {
+        let throughput = self.throughput();
+        if throughput > 0.0 {
+            let remaining = self.bytes_total.saturating_sub(self.bytes_sent);
+            Some(Duration::from_secs_f64(remaining as f64 / throughput))
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Default)]
+struct FileProgress {
+    bytes_sent: u64,
+    size: u64,
+    done: bool,
+    verified: bool,
+    failed: bool,
+}
+
+#[derive(Default)]
+struct AggregatorState {
+    started_at: Option<Instant>,
+    last_event_at: Option<Instant>,
+    files: HashMap<PathBuf, FileProgress>,
+    chunks_retried: usize,
+    imports_completed: usize,
+}
+
+/// A `ProgressCallback` that aggregates the events of an upload, which
+/// may span several files and imports, into a `ProgressSummary`.
+///
+/// Every event is also passed along to an optional inner callback.
+/// Cloning shares the underlying state, so a clone can be handed to
+/// the upload functions while the original is used for reporting.
+#[derive(Clone)]
+pub struct ProgressAggregator {
+    state: Arc<Mutex<AggregatorState>>,
+    inner: Option<Arc<dyn ProgressCallback>>,
+}
+
+impl Default for ProgressAggregator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ProgressAggregator {
+    pub fn new() -> Self {
+        Self {
+            state: Default::default(),
+            inner: None,
+        }
+    }
+
+    /// Pass every event along to the given callback as well.
+    pub fn with_callback<C: 'static + ProgressCallback>(mut self, callback: C) -> Self {
+        self.inner = Some(Arc::new(callback));
+        self
+    }
+
+    /// Count every file of a preview towards the totals before any of
+    /// them is started, so the percentage and estimate cover the whole
+    /// upload. `path` is the directory passed to the upload functions;
+    /// files are keyed by their path within the upload under it, as in
+    /// every event of the upload.
+    pub fn expect_preview<P: AsRef<Path>>(&self, path: P, preview: &UploadPreview) {
+        let mut state = self.state.lock().unwrap();
+        for file in preview.packages().iter().flat_map(|p| p.files()) {
+            state
+                .files
+                .entry(path.as_ref().join(file.relative_path()))
+                .or_default()
+                .size = file.size();
+        }
+    }
+
+    /// Returns a snapshot of the progress so far.
+    pub fn summary(&self) -> ProgressSummary {
+        let state = self.state.lock().unwrap();
+        let files = state.files.values();
+        ProgressSummary {
+            files_total: state.files.len(),
+            files_completed: files.clone().filter(|f| f.done).count(),
+            files_verified: files.clone().filter(|f| f.verified).count(),
+            files_failed: files.clone().filter(|f| f.failed).count(),
+            chunks_retried: state.chunks_retried,
+            imports_completed: state.imports_completed,
+            bytes_sent: files.clone().map(|f| f.bytes_sent).sum(),
+            bytes_total: files.map(|f| f.size).sum(),
+            elapsed: match (state.started_at, state.last_event_at) {
+                (Some(start), Some(last)) => last.duration_since(start),
+                _ => Duration::from_secs(0),
+            },
+        }
+    }
+
+    fn record(&self, event: &ProgressEvent, now: Instant) {
+        let mut state = self.state.lock().unwrap();
+        state.started_at.get_or_insert(now);
+        state.last_event_at = Some(now);
+
+        match event {
+            ProgressEvent::FileStarted {
+                file_path, size, ..
+            } => {
+                state.files.entry(file_path.clone()).or_default().size = *size;
+            }
+            ProgressEvent::ChunkSent(update) => {
+                let file = state
+                    .files
+                    .entry(update.file_path().to_path_buf())
+                    .or_default();
+                file.size = update.size();
+                // Updates of chunks sent in parallel may arrive out of order:
+                file.bytes_sent = file.bytes_sent.max(update.bytes_sent());
+                file.done = file.done || update.is_done();
+            }
+            ProgressEvent::ChunkRetried { .. } => state.chunks_retried += 1,
+            ProgressEvent::FileVerified { file_path, .. } => {
+                state.files.entry(file_path.clone()).or_default().verified = true;
+            }
+            ProgressEvent::FileFailed { file_path, .. } => {
+                state.files.entry(file_path.clone()).or_default().failed = true;
+            }
+            ProgressEvent::ImportCompleted { .. } => state.imports_completed += 1,
+        }
+    }
+}
+
+impl ProgressCallback for ProgressAggregator {
+    fn on_update(&self, update: &ProgressUpdate) {
+        self.on_event(&ProgressEvent::ChunkSent(update.clone()))
+    }
+
+    fn on_event(&self, event: &ProgressEvent) {
+        self.record(event, Instant::now());
+        if let Some(ref inner) = self.inner {
+            inner.on_event(event);
         }
     }
 }
 
 /// A type representing progress updates for an upload.
-#[derive(Debug, Clone, Hash)]
+#[derive(Debug, Clone, Hash, PartialEq)]
 pub struct ProgressUpdate {
     part_number: usize,
     import_id: ImportId,
@@ -115,3 +389,151 @@ impl ProgressUpdate {
         (self.bytes_sent as f32 / self.size as f32) * 100.0
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Clone, Default)]
+    struct Counter(Arc<Mutex<usize>>);
+
+    impl ProgressCallback for Counter {
+        fn on_update(&self, _update: &ProgressUpdate) {
+            *self.0.lock().unwrap() += 1;
+        }
+    }
+
+    fn chunk_sent(file_name: &str, bytes_sent: u64, size: u64) -> ProgressEvent {
+        ProgressEvent::ChunkSent(ProgressUpdate::new(
+            1,
+            ImportId::new("import id"),
+            PathBuf::from(file_name),
+            bytes_sent,
+            size,
+            bytes_sent == size,
+        ))
+    }
+
+    #[test]
+    fn shared_callbacks_receive_every_update() {
+        let counter = Counter::default();
+        let shared: Arc<Box<dyn ProgressCallback>> = Arc::new(Box::new(counter.clone()));
+        let other = shared.clone();
+
+        shared.on_event(&chunk_sent("example.csv", 50, 100));
+        other.on_event(&chunk_sent("example.csv", 100, 100));
+
+        assert_eq!(*counter.0.lock().unwrap(), 2);
+    }
+
+    #[test]
+    fn aggregator_totals_progress_across_files() {
+        let inner = Counter::default();
+        let aggregator = ProgressAggregator::new().with_callback(inner.clone());
+        let import_id = ImportId::new("import id");
+        let start = Instant::now();
+
+        for (file_name, size) in &[("example.csv", 100), ("brain.jpg", 300)] {
+            aggregator.record(
+                &ProgressEvent::FileStarted {
+                    import_id: import_id.clone(),
+                    file_path: PathBuf::from(file_name),
+                    size: *size,
+                },
+                start,
+            );
+        }
+        aggregator.record(&chunk_sent("example.csv", 100, 100), start);
+        aggregator.record(
+            &ProgressEvent::ChunkRetried {
+                import_id: import_id.clone(),
+                file_path: PathBuf::from("brain.jpg"),
+                chunk_number: 0,
+                attempt: 1,
+            },
+            start + Duration::from_secs(1),
+        );
+        aggregator.record(
+            &chunk_sent("brain.jpg", 100, 300),
+            start + Duration::from_secs(2),
+        );
+
+        let summary = aggregator.summary();
+        assert_eq!(summary.files_total(), 2);
+        assert_eq!(summary.files_completed(), 1);
+        assert_eq!(summary.chunks_retried(), 1);
+        assert_eq!(summary.bytes_sent(), 200);
+        assert_eq!(summary.bytes_total(), 400);
+        assert_eq!(summary.percent_done(), 50.0);
+        assert_eq!(summary.throughput(), 100.0);
+        assert_eq!(summary.eta(), Some(Duration::from_secs(2)));
+
+        // events recorded directly are not forwarded, but events sent
+        // through the callback are:
+        aggregator.on_event(&chunk_sent("brain.jpg", 300, 300));
+        assert_eq!(*inner.0.lock().unwrap(), 1);
+        assert_eq!(aggregator.summary().files_completed(), 2);
+    }
+
+    #[test]
+    fn aggregator_counts_expected_files_before_they_start() {
+        let aggregator = ProgressAggregator::new();
+        let preview: UploadPreview = serde_json::from_str(
+            r#"{
+                "packages": [{
+                    "packageName": "example",
+                    "importId": "import id",
+                    "files": [
+                        {"fileName": "example.csv", "size": 100},
+                        {"fileName": "brain.jpg", "size": 300}
+                    ],
+                    "groupSize": 2
+                }]
+            }"#,
+        )
+        .unwrap();
+        aggregator.expect_preview("", &preview);
+
+        aggregator.on_event(&chunk_sent("example.csv", 100, 100));
+
+        let summary = aggregator.summary();
+        assert_eq!(summary.files_total(), 2);
+        assert_eq!(summary.bytes_total(), 400);
+        assert_eq!(summary.percent_done(), 25.0);
+    }
+
+    #[test]
+    fn aggregator_ignores_updates_arriving_out_of_order() {
+        let aggregator = ProgressAggregator::new();
+
+        aggregator.on_event(&chunk_sent("example.csv", 200, 300));
+        aggregator.on_event(&chunk_sent("example.csv", 100, 300));
+
+        assert_eq!(aggregator.summary().bytes_sent(), 200);
+    }
+
+    #[test]
+    fn aggregator_counts_verified_and_failed_files() {
+        let aggregator = ProgressAggregator::new();
+        let import_id = ImportId::new("import id");
+
+        aggregator.on_event(&ProgressEvent::FileVerified {
+            import_id: import_id.clone(),
+            file_path: PathBuf::from("example.csv"),
+        });
+        aggregator.on_event(&ProgressEvent::FileFailed {
+            import_id: import_id.clone(),
+            file_path: PathBuf::from("brain.jpg"),
+            error: ErrorKind::UploadError {
+                message: "failed".to_string(),
+            },
+        });
+        aggregator.on_event(&ProgressEvent::ImportCompleted { import_id });
+
+        let summary = aggregator.summary();
+        assert_eq!(summary.files_verified(), 1);
+        assert_eq!(summary.files_failed(), 1);
+        assert_eq!(summary.imports_completed(), 1);
+        assert_eq!(summary.eta(), None);
+    }
+}
@@ -0,0 +1,244 @@
+// Copyright (c) 2018 Blackfynn, Inc. All Rights Reserved.
+
+use std::slice;
+use std::vec;
+
+use crate::bf::model::{ImportId, PackageId};
+use crate::bf::{Error, ErrorKind, Result};
+
+/// The outcome of uploading a single file.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum FileStatus {
+    /// All chunks of the file were received by the upload service.
+    Uploaded,
+    /// The file was uploaded and its hash matches the hash computed locally.
+    Verified,
+    /// The file was not sent, as an identical package already exists.
+    Skipped,
+    /// The upload was cancelled before every chunk of the file was sent.
+    /// Resuming the upload will send the remaining chunks.
+    Cancelled,
+    /// The file could not be uploaded or verified.
+    Failed(ErrorKind),
+}
+
+/// A per-file entry of an `UploadReport`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FileReport {
+    file_name: String,
+    status: FileStatus,
+    local_hash: Option<String>,
+    remote_hash: Option<String>,
+}
+
+impl FileReport {
+    pub fn new<S: Into<String>>(file_name: S, status: FileStatus) -> Self {
+        Self {
+            file_name: file_name.into(),
+            status,
+            local_hash: None,
+            remote_hash: None,
+        }
+    }
+
+    /// Build a report entry by comparing the locally computed hash of a
+    /// file against the hash returned by the upload service.
+    pub fn from_hashes<S, L, R>(file_name: S, local_hash: L, remote_hash: R) -> Self
+    where
+        S: Into<String>,
+        L: Into<String>,
+        R: Into<String>,
+    {
+        let file_name = file_name.into();
+        let local_hash = local_hash.into();
+        let remote_hash = remote_hash.into();
+
+        let status = if local_hash == remote_hash {
+            FileStatus::Verified
+        } else {
+            FileStatus::Failed(ErrorKind::ChecksumMismatch {
+                file_name: file_name.clone(),
+                expected: local_hash.clone(),
+                actual: remote_hash.clone(),
+            })
+        };
+
+        Self {
+            file_name,
+            status,
+            local_hash: Some(local_hash),
+            remote_hash: Some(remote_hash),
+        }
+    }
+
+    pub fn file_name(&self) -> &String {
+        &self.file_name
+    }
+
+    pub fn status(&self) -> &FileStatus {
+        &self.status
+    }
+
+    /// The hash computed locally while the file was being sent.
+    pub fn local_hash(&self) -> Option<&String> {
+        self.local_hash.as_ref()
+    }
+
+    /// The hash reported by the upload service.
+    pub fn remote_hash(&self) -> Option<&String> {
+        self.remote_hash.as_ref()
+    }
+
+    /// Returns the reason the file failed, if it did.
+    pub fn error(&self) -> Option<&ErrorKind> {
+        match self.status {
+            FileStatus::Failed(ref kind) => Some(kind),
+            _ => None,
+        }
+    }
+}
+
+/// A summary of the files sent as part of a single import.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct UploadReport {
+    import_id: ImportId,
+    files: Vec<FileReport>,
+    package_id: Option<PackageId>,
+}
+
+impl UploadReport {
+    pub fn new(import_id: ImportId, files: Vec<FileReport>) -> Self {
+        Self {
+            import_id,
+            files,
+            package_id: None,
+        }
+    }
+
+    pub fn with_package_id(mut self, package_id: Option<PackageId>) -> Self {
+        self.package_id = package_id;
+        self
+    }
+
+    pub fn import_id(&self) -> &ImportId {
+        &self.import_id
+    }
+
+    /// The package the files were imported into, as reported by the
+    /// platform once the import completed.
+    pub fn package_id(&self) -> Option<&PackageId> {
+        self.package_id.as_ref()
+    }
+
+    pub fn files(&self) -> &Vec<FileReport> {
+        &self.files
+    }
+
+    pub fn iter(&self) -> slice::Iter<'_, FileReport> {
+        self.files.iter()
+    }
+
+    /// Returns the entries of every file that failed.
+    pub fn failures(&self) -> Vec<&FileReport> {
+        self.files.iter().filter(|f| f.error().is_some()).collect()
+    }
+
+    /// Returns the entries of every file left incomplete by a cancelled
+    /// upload.
+    pub fn incomplete(&self) -> Vec<&FileReport> {
+        self.files
+            .iter()
+            .filter(|f| f.status() == &FileStatus::Cancelled)
+            .collect()
+    }
+
+    /// Tests if the upload was cancelled before every file was sent. A
+    /// cancelled upload can be resumed with `Blackfynn::resume_upload`.
+    pub fn is_cancelled(&self) -> bool {
+        !self.incomplete().is_empty()
+    }
+
+    /// Tests if every file in the report was uploaded without error.
+    pub fn is_success(&self) -> bool {
+        self.failures().is_empty() && !self.is_cancelled()
+    }
+
+    /// Converts the report into an error describing the first failed
+    /// file, if there was one, or `ErrorKind::Cancelled` if the upload
+    /// was cancelled.
+    pub fn into_result(self) -> Result<Self> {
+        match self.files.iter().find_map(|f| f.error().cloned()) {
+            Some(kind) => Err(Error::from(kind)),
+            None if self.is_cancelled() => Err(Error::cancelled()),
+            None => Ok(self),
+        }
+    }
+}
+
+impl IntoIterator for UploadReport {
+    type Item = FileReport;
+    type IntoIter = vec::IntoIter<FileReport>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.files.into_iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matching_hashes_are_verified() {
+        let file = FileReport::from_hashes("example.csv", "abc", "abc");
+        assert_eq!(file.status(), &FileStatus::Verified);
+        assert!(file.error().is_none());
+    }
+
+    #[test]
+    fn mismatched_hashes_are_reported_as_failures() {
+        let report = UploadReport::new(
+            ImportId::new("import id"),
+            vec![
+                FileReport::from_hashes("example.csv", "abc", "abc"),
+                FileReport::from_hashes("brain.jpg", "abc", "def"),
+            ],
+        );
+
+        assert!(!report.is_success());
+        assert_eq!(report.failures().len(), 1);
+        assert_eq!(report.failures()[0].file_name(), "brain.jpg");
+
+        match report.into_result() {
+            Err(err) => assert_eq!(
+                err.kind(),
+                &ErrorKind::ChecksumMismatch {
+                    file_name: "brain.jpg".to_string(),
+                    expected: "abc".to_string(),
+                    actual: "def".to_string(),
+                }
+            ),
+            Ok(_) => panic!("expected a checksum mismatch"),
+        }
+    }
+
+    #[test]
+    fn cancelled_uploads_list_incomplete_files() {
+        let report = UploadReport::new(
+            ImportId::new("import id"),
+            vec![
+                FileReport::new("example.csv", FileStatus::Uploaded),
+                FileReport::new("brain.jpg", FileStatus::Cancelled),
+            ],
+        );
+
+        assert!(report.is_cancelled());
+        assert!(!report.is_success());
+        assert_eq!(report.incomplete().len(), 1);
+        assert_eq!(report.incomplete()[0].file_name(), "brain.jpg");
+        assert_eq!(
+            report.into_result().unwrap_err().kind(),
+            &ErrorKind::Cancelled
+        );
+    }
+}
@@ -0,0 +1,237 @@
+// Copyright (c) 2018 Blackfynn, Inc. All Rights Reserved.
+
+use chrono::{DateTime, Utc};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{Map, Number, Value};
+
+use crate::bf::api::request;
+use crate::bf::model::{self, ConceptProperty, PropertyType, PropertyValue, RecordValue};
+use crate::bf::{Error, Result};
+
+/// Maps a Rust type to the schema of a metadata concept, so values of the
+/// type can be stored as records.
+///
+/// Types deriving `Serialize` and `Deserialize` only need to name their
+/// concept and describe its properties; the conversions to and from
+/// records are derived from the serialized fields:
+///
+/// ```ignore
+/// #[derive(Serialize, Deserialize)]
+/// struct Subject {
+///     name: String,
+///     age: i64,
+/// }
+///
+/// impl RecordSchema for Subject {
+///     fn concept_name() -> String {
+///         "subject".to_string()
+///     }
+///
+///     fn properties() -> Vec<ConceptProperty> {
+///         vec![
+///             ConceptProperty::new("name", PropertyType::String).with_title(true),
+///             ConceptProperty::new("age", PropertyType::Long),
+///         ]
+///     }
+/// }
+/// ```
+pub trait RecordSchema: Serialize + DeserializeOwned + Send + 'static {
+    /// The name of the concept values of the type are stored as.
+    fn concept_name() -> String;
+
+    /// The properties of the concept, one per serialized field.
+    fn properties() -> Vec<ConceptProperty>;
+
+    /// Convert a value into the values of a record.
+    fn to_record(&self) -> Result<request::concept::Record> {
+        record_from_value(serde_json::to_value(self)?, &Self::properties())
+    }
+
+    /// Convert a record back into a value.
+    fn from_record(record: &model::Record) -> Result<Self> {
+        Ok(serde_json::from_value(value_from_record(record))?)
+    }
+}
+
+// Build a record from a serialized struct, typing each field according to
+// the schema. Null fields are left unset.
+fn record_from_value(value: Value, schema: &[ConceptProperty]) -> Result<request::concept::Record> {
+    let fields = match value {
+        Value::Object(fields) => fields,
+        other => {
+            return Err(Error::invalid_arguments(format!(
+                "records must serialize to an object, got {}",
+                other
+            )))
+        }
+    };
+
+    let mut values = vec![];
+    for (name, field) in fields {
+        let property = schema
+            .iter()
+            .find(|p| p.name() == &name)
+            .ok_or_else(|| Error::invalid_arguments(format!("no such property: {}", name)))?;
+
+        let value = match (field, property.data_type()) {
+            (Value::Null, _) => continue,
+            (Value::Bool(b), PropertyType::Boolean) => PropertyValue::Boolean(b),
+            (Value::Number(ref n), PropertyType::Long) if n.is_i64() => {
+                PropertyValue::Long(n.as_i64().unwrap())
+            }
+            (Value::Number(ref n), PropertyType::Double) if n.as_f64().is_some() => {
+                PropertyValue::Double(n.as_f64().unwrap())
+            }
+            (Value::String(s), PropertyType::Date) => s
+                .parse::<DateTime<Utc>>()
+                .map(PropertyValue::Date)
+                .map_err(|_| {
+                    Error::invalid_arguments(format!("property {} is not a date: {}", name, s))
+                })?,
+            (Value::String(s), PropertyType::String) => PropertyValue::String(s),
+            (field, data_type) => {
+                return Err(Error::invalid_arguments(format!(
+                    "property {} expects a {} value, got {}",
+                    name, data_type, field
+                )))
+            }
+        };
+
+        values.push(RecordValue::new(name, value));
+    }
+
+    Ok(request::concept::Record::new(values))
+}
+
+// The inverse of `record_from_value`.
+fn value_from_record(record: &model::Record) -> Value {
+    let fields: Map<String, Value> = record
+        .values()
+        .iter()
+        .map(|v| {
+            let value = match *v.value() {
+                PropertyValue::Boolean(b) => Value::Bool(b),
+                PropertyValue::Long(n) => Value::Number(n.into()),
+                PropertyValue::Double(n) => Number::from_f64(n).map_or(Value::Null, Value::Number),
+                PropertyValue::Date(ref d) => Value::String(d.to_rfc3339()),
+                PropertyValue::String(ref s) => Value::String(s.clone()),
+            };
+            (v.name().clone(), value)
+        })
+        .collect();
+    Value::Object(fields)
+}
+
+/// Compute the properties to create or update so the schema of a concept
+/// matches `wanted`. Properties cannot change type once created; properties
+/// missing from `wanted` are kept.
+pub fn schema_changes(
+    current: &[ConceptProperty],
+    wanted: &[ConceptProperty],
+) -> Result<Vec<ConceptProperty>> {
+    let mut changes = vec![];
+    for property in wanted {
+        match current.iter().find(|p| p.name() == property.name()) {
+            Some(existing) if existing.data_type() != property.data_type() => {
+                return Err(Error::invalid_arguments(format!(
+                    "property {} is a {}, and cannot be changed to a {}",
+                    property.name(),
+                    existing.data_type(),
+                    property.data_type()
+                )))
+            }
+            Some(existing) if existing == property => {}
+            _ => changes.push(property.clone()),
+        }
+    }
+    Ok(changes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_derive::{Deserialize, Serialize};
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Subject {
+        name: String,
+        age: i64,
+        weight: f64,
+        enrolled: DateTime<Utc>,
+        notes: Option<String>,
+    }
+
+    impl RecordSchema for Subject {
+        fn concept_name() -> String {
+            "subject".to_string()
+        }
+
+        fn properties() -> Vec<ConceptProperty> {
+            vec![
+                ConceptProperty::new("name", PropertyType::String).with_title(true),
+                ConceptProperty::new("age", PropertyType::Long),
+                ConceptProperty::new("weight", PropertyType::Double),
+                ConceptProperty::new("enrolled", PropertyType::Date),
+                ConceptProperty::new("notes", PropertyType::String),
+            ]
+        }
+    }
+
+    #[test]
+    fn values_round_trip_through_records() {
+        let subject = Subject {
+            name: "subject 1".to_string(),
+            age: 31,
+            weight: 62.0,
+            enrolled: "2019-04-01T12:00:00Z".parse().unwrap(),
+            // Strings that look like dates stay strings:
+            notes: Some("2019-04-01T12:00:00Z".to_string()),
+        };
+
+        let record = subject.to_record().unwrap();
+        record.validate(&Subject::properties()).unwrap();
+        assert_eq!(
+            record
+                .values()
+                .iter()
+                .find(|v| v.name() == "enrolled")
+                .unwrap()
+                .value(),
+            &PropertyValue::Date(subject.enrolled)
+        );
+
+        let stored: model::Record = serde_json::from_value(serde_json::json!({
+            "id": "N:record:1",
+            "values": serde_json::to_value(record.values()).unwrap(),
+        }))
+        .unwrap();
+        let stored = stored.with_schema(&Subject::properties()).unwrap();
+        assert_eq!(Subject::from_record(&stored).unwrap(), subject);
+    }
+
+    #[test]
+    fn schema_changes_only_include_new_and_updated_properties() {
+        let current = vec![
+            ConceptProperty::new("name", PropertyType::String).with_title(true),
+            ConceptProperty::new("age", PropertyType::Long),
+        ];
+        let wanted = vec![
+            ConceptProperty::new("name", PropertyType::String).with_title(true),
+            ConceptProperty::new("age", PropertyType::Long).with_display_name("Age"),
+            ConceptProperty::new("weight", PropertyType::Double),
+        ];
+
+        let changes = schema_changes(&current, &wanted).unwrap();
+        assert_eq!(
+            changes
+                .iter()
+                .map(|p| p.name().as_str())
+                .collect::<Vec<_>>(),
+            vec!["age", "weight"]
+        );
+
+        let retyped = vec![ConceptProperty::new("age", PropertyType::String)];
+        assert!(schema_changes(&current, &retyped).is_err());
+    }
+}
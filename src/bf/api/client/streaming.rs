@@ -0,0 +1,573 @@
+// Copyright (c) 2018 Blackfynn, Inc. All Rights Reserved.
+
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, Write};
+use std::mem;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use chrono::{Duration, Utc};
+use futures::future::Shared;
+use futures::{Future as _Future, Stream as _Stream, *};
+
+use super::Blackfynn;
+use crate::bf::api::request;
+use crate::bf::model::{self, ChannelId, PackageId, SampleBlock, StreamBlock};
+use crate::bf::util::futures::into_future_trait;
+use crate::bf::{Error, ErrorKind, Future, Result};
+
+/// The default number of samples sent per block.
+pub const DEFAULT_STREAM_BLOCK_SIZE: usize = 1000;
+
+/// The default number of blocks a writer holds before it waits for them to
+/// be sent.
+pub const DEFAULT_MAX_PENDING_BLOCKS: usize = 100;
+
+// Streaming credentials expiring sooner than this are refreshed before use:
+const CREDENTIAL_REFRESH_MARGIN_SECS: i64 = 60;
+
+// The spool file holding the sequence number of the next block:
+const SEQUENCE_FILE_NAME: &str = "sequence";
+
+// The spool file holding the ID of the package the blocks belong to:
+const PACKAGE_FILE_NAME: &str = "package";
+
+const BLOCK_FILE_EXTENSION: &str = "block";
+
+/// Options controlling how a `TimeseriesWriter` batches samples.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StreamingOptions {
+    block_size: usize,
+    max_pending_blocks: usize,
+}
+
+impl Default for StreamingOptions {
+    fn default() -> Self {
+        Self {
+            block_size: DEFAULT_STREAM_BLOCK_SIZE,
+            max_pending_blocks: DEFAULT_MAX_PENDING_BLOCKS,
+        }
+    }
+}
+
+impl StreamingOptions {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// The number of samples of a channel collected into each block.
+    pub fn with_block_size(mut self, block_size: usize) -> Self {
+        self.block_size = block_size.max(1);
+        self
+    }
+
+    /// The number of unsent blocks after which writes wait for the spool
+    /// to be flushed, and fail if it cannot be.
+    pub fn with_max_pending_blocks(mut self, max_pending_blocks: usize) -> Self {
+        self.max_pending_blocks = max_pending_blocks.max(1);
+        self
+    }
+
+    pub fn block_size(&self) -> usize {
+        self.block_size
+    }
+
+    pub fn max_pending_blocks(&self) -> usize {
+        self.max_pending_blocks
+    }
+}
+
+/// A directory holding the blocks written by a `TimeseriesWriter` until
+/// they are acknowledged by the platform.
+///
+/// Every block is synced to disk before it is considered written, so
+/// reopening the spool after a crash recovers every unsent block, and
+/// sequence numbers carry on from where they left off. A spool belongs to
+/// a single package while it holds unsent blocks.
+#[derive(Debug)]
+pub struct Spool {
+    dir: PathBuf,
+    next_sequence: u64,
+    len: usize,
+}
+
+impl Spool {
+    /// Open the spool of the package `package_id` in `dir`, creating the
+    /// directory if needed. Fails if the spool holds unsent blocks of
+    /// another package.
+    pub fn open<P: AsRef<Path>>(dir: P, package_id: &PackageId) -> Result<Self> {
+        let dir = dir.as_ref().to_path_buf();
+        fs::create_dir_all(&dir)?;
+
+        let owner = match fs::read_to_string(dir.join(PACKAGE_FILE_NAME)) {
+            Ok(owner) => Some(PackageId::new(owner.trim())),
+            Err(ref err) if err.kind() == io::ErrorKind::NotFound => None,
+            Err(err) => return Err(err.into()),
+        };
+
+        let stored = match fs::read_to_string(dir.join(SEQUENCE_FILE_NAME)) {
+            Ok(sequence) => sequence.trim().parse::<u64>()?,
+            Err(ref err) if err.kind() == io::ErrorKind::NotFound => 0,
+            Err(err) => return Err(err.into()),
+        };
+
+        let mut spool = Self {
+            dir,
+            next_sequence: stored,
+            len: 0,
+        };
+
+        // A crash may have happened after a block was written, but before
+        // the sequence file was updated:
+        let sequences = spool.sequences()?;
+        if let Some(last) = sequences.last() {
+            spool.next_sequence = spool.next_sequence.max(last + 1);
+        }
+        spool.len = sequences.len();
+
+        match owner {
+            Some(ref owner) if owner == package_id => {}
+            Some(ref owner) if !spool.is_empty() => {
+                return Err(Error::invalid_arguments(format!(
+                    "the spool in {:?} holds unsent blocks of package {}, not {}",
+                    spool.dir, owner, package_id
+                )));
+            }
+            _ => write_synced(
+                &spool.dir.join(PACKAGE_FILE_NAME),
+                package_id.to_string().as_bytes(),
+            )?,
+        }
+
+        Ok(spool)
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// The sequence number the next appended block will be given.
+    pub fn next_sequence(&self) -> u64 {
+        self.next_sequence
+    }
+
+    /// Number and durably store a block of samples.
+    pub fn append(&mut self, samples: SampleBlock) -> Result<StreamBlock> {
+        let block = StreamBlock::new(self.next_sequence, samples);
+        write_synced(
+            &self.block_path(block.sequence()),
+            &serde_json::to_vec(&block)?,
+        )?;
+
+        self.next_sequence += 1;
+        self.len += 1;
+        write_synced(
+            &self.dir.join(SEQUENCE_FILE_NAME),
+            self.next_sequence.to_string().as_bytes(),
+        )?;
+
+        Ok(block)
+    }
+
+    /// The unsent blocks, in the order they were appended.
+    pub fn pending(&self) -> Result<Vec<StreamBlock>> {
+        self.sequences()?
+            .into_iter()
+            .map(|sequence| {
+                let data = fs::read(self.block_path(sequence))?;
+                Ok(serde_json::from_slice(&data)?)
+            })
+            .collect()
+    }
+
+    /// The number of unsent blocks.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Discard a block once it has been sent.
+    pub fn remove(&mut self, sequence: u64) -> Result<()> {
+        match fs::remove_file(self.block_path(sequence)) {
+            Ok(()) => {
+                self.len -= 1;
+                Ok(())
+            }
+            Err(ref err) if err.kind() == io::ErrorKind::NotFound => Ok(()),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    fn block_path(&self, sequence: u64) -> PathBuf {
+        self.dir
+            .join(format!("{:020}.{}", sequence, BLOCK_FILE_EXTENSION))
+    }
+
+    fn sequences(&self) -> Result<Vec<u64>> {
+        let mut sequences = vec![];
+        for entry in fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some(BLOCK_FILE_EXTENSION) {
+                continue;
+            }
+            if let Some(sequence) = path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .and_then(|stem| stem.parse::<u64>().ok())
+            {
+                sequences.push(sequence);
+            }
+        }
+        sequences.sort();
+        Ok(sequences)
+    }
+}
+
+// Write a file next to its destination, sync it, then move it into place,
+// so a crash never leaves a partially written file behind.
+fn write_synced(path: &Path, data: &[u8]) -> Result<()> {
+    let tmp = path.with_extension("tmp");
+    let mut file = fs::File::create(&tmp)?;
+    file.write_all(data)?;
+    file.sync_all()?;
+    fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+#[derive(Debug, Default)]
+struct SampleBuffer {
+    timestamps: Vec<i64>,
+    values: Vec<f64>,
+}
+
+impl SampleBuffer {
+    fn take(&mut self, channel_id: &ChannelId) -> SampleBlock {
+        SampleBlock::new(
+            channel_id.clone(),
+            mem::take(&mut self.timestamps),
+            mem::take(&mut self.values),
+        )
+    }
+}
+
+#[derive(Debug)]
+struct WriterState {
+    spool: Spool,
+    buffers: HashMap<ChannelId, SampleBuffer>,
+    credential: Option<model::TemporaryCredential>,
+    send_error: Option<ErrorKind>,
+}
+
+impl WriterState {
+    // Buffer samples of a channel, spooling a block every `block_size`
+    // samples. Returns the number of unsent blocks.
+    fn push(
+        &mut self,
+        channel_id: &ChannelId,
+        samples: &[(i64, f64)],
+        block_size: usize,
+    ) -> Result<usize> {
+        let buffer = self.buffers.entry(channel_id.clone()).or_default();
+
+        for &(timestamp, value) in samples {
+            buffer.timestamps.push(timestamp);
+            buffer.values.push(value);
+            if buffer.values.len() >= block_size {
+                self.spool.append(buffer.take(channel_id))?;
+            }
+        }
+
+        Ok(self.spool.len())
+    }
+
+    // Spool the samples of every partially filled block.
+    fn drain(&mut self) -> Result<()> {
+        for (channel_id, buffer) in self.buffers.iter_mut() {
+            if !buffer.values.is_empty() {
+                self.spool.append(buffer.take(channel_id))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Streams samples to the channels of a timeseries package as they are
+/// acquired.
+///
+/// Samples are buffered per channel and cut into fixed size blocks, which
+/// are numbered and stored in a local `Spool` before being sent. Blocks
+/// are only discarded once the platform acknowledges them, so nothing is
+/// lost if the connection drops: flushing again, or reopening the spool
+/// after a crash, sends whatever is left. Once `max_pending_blocks` blocks
+/// are waiting, `write` sends them before spooling more samples, and fails
+/// if they cannot be sent.
+///
+/// Clones of a writer share their buffers and spool, and their flushes run
+/// one at a time.
+#[derive(Clone)]
+pub struct TimeseriesWriter {
+    bf: Blackfynn,
+    package_id: PackageId,
+    options: StreamingOptions,
+    state: Arc<Mutex<WriterState>>,
+    // The most recently started flush, which the next flush waits for:
+    last_flush: Arc<Mutex<Option<Shared<Future<()>>>>>,
+}
+
+impl TimeseriesWriter {
+    pub(super) fn open<P: AsRef<Path>>(
+        bf: Blackfynn,
+        package_id: PackageId,
+        spool_dir: P,
+        options: StreamingOptions,
+    ) -> Result<Self> {
+        let spool = Spool::open(spool_dir, &package_id)?;
+        Ok(Self {
+            bf,
+            package_id,
+            options,
+            state: Arc::new(Mutex::new(WriterState {
+                spool,
+                buffers: HashMap::new(),
+                credential: None,
+                send_error: None,
+            })),
+            last_flush: Arc::new(Mutex::new(None)),
+        })
+    }
+
+    pub fn package_id(&self) -> &PackageId {
+        &self.package_id
+    }
+
+    /// Create a channel on the package to write samples to.
+    pub fn create_channel(&self, channel: request::channel::Channel) -> Future<model::Channel> {
+        self.bf.create_channel(self.package_id.clone(), channel)
+    }
+
+    /// The number of blocks spooled, but not yet sent.
+    pub fn pending_blocks(&self) -> usize {
+        self.state.lock().unwrap().spool.len()
+    }
+
+    /// The error of the last failed attempt to send the spooled blocks,
+    /// if the blocks have not been sent since.
+    pub fn send_error(&self) -> Option<ErrorKind> {
+        self.state.lock().unwrap().send_error.clone()
+    }
+
+    /// Write `(timestamp, value)` samples to a channel. Timestamps are in
+    /// microseconds.
+    ///
+    /// Succeeds once the samples are spooled, so a failed write can be
+    /// retried without duplicating samples. Once `max_pending_blocks`
+    /// blocks are waiting, the spool is flushed first, and the write fails
+    /// without spooling anything if the flush does. Blocks that could not
+    /// be sent stay in the spool; see `pending_blocks` and `send_error`.
+    pub fn write(&self, channel_id: &ChannelId, samples: &[(i64, f64)]) -> Future<()> {
+        if self.pending_blocks() < self.options.max_pending_blocks() {
+            return into_future_trait(future::result(self.push(channel_id, samples)));
+        }
+
+        let writer = self.clone();
+        let channel_id = channel_id.clone();
+        let samples = samples.to_vec();
+        into_future_trait(
+            self.flush()
+                .and_then(move |_| writer.push(&channel_id, &samples)),
+        )
+    }
+
+    /// Send every spooled block, in order. Blocks that could not be sent
+    /// stay in the spool, and are sent by the next flush. A flush started
+    /// while another is running waits for it to finish.
+    pub fn flush(&self) -> Future<()> {
+        let writer = self.clone();
+        let mut last_flush = self.last_flush.lock().unwrap();
+
+        let previous = match last_flush.take() {
+            Some(previous) => into_future_trait(previous.then(|_| Ok(()))),
+            None => into_future_trait(future::ok(())),
+        };
+        let flush = into_future_trait(previous.and_then(move |_| writer.send_pending())).shared();
+        *last_flush = Some(flush.clone());
+
+        into_future_trait(flush.map(|_| ()).map_err(|err| (*err).clone()))
+    }
+
+    /// Spool the samples of partially filled blocks, then send everything
+    /// left in the spool.
+    pub fn close(self) -> Future<()> {
+        let drained = self.state.lock().unwrap().drain();
+        match drained {
+            Ok(_) => self.flush(),
+            Err(err) => into_future_trait(future::err(err)),
+        }
+    }
+
+    // Buffer samples of a channel, spooling every block filled.
+    fn push(&self, channel_id: &ChannelId, samples: &[(i64, f64)]) -> Result<()> {
+        self.state
+            .lock()
+            .unwrap()
+            .push(channel_id, samples, self.options.block_size())
+            .map(|_| ())
+    }
+
+    // Send the spooled blocks, recording the error if any could not be
+    // sent.
+    fn send_pending(&self) -> Future<()> {
+        let writer = self.clone();
+
+        let f = self.credential().and_then(move |credential| {
+            let pending = writer.state.lock().unwrap().spool.pending();
+            future::result(pending).and_then(move |blocks| {
+                stream::iter_ok(blocks).for_each(move |block| {
+                    let state = Arc::clone(&writer.state);
+                    let sequence = block.sequence();
+                    writer
+                        .bf
+                        .send_timeseries_block(writer.package_id.clone(), &credential, &block)
+                        .and_then(move |_| state.lock().unwrap().spool.remove(sequence))
+                })
+            })
+        });
+
+        let state = Arc::clone(&self.state);
+        let f = f.then(move |result| {
+            state.lock().unwrap().send_error = result.as_ref().err().map(|err| err.kind().clone());
+            result
+        });
+
+        into_future_trait(f)
+    }
+
+    // The current streaming credential, refreshed if it is about to expire.
+    fn credential(&self) -> Future<model::TemporaryCredential> {
+        let current = self.state.lock().unwrap().credential.clone();
+        match current {
+            Some(ref credential)
+                if *credential.expiration() - Utc::now()
+                    > Duration::seconds(CREDENTIAL_REFRESH_MARGIN_SECS) =>
+            {
+                into_future_trait(future::ok(credential.clone()))
+            }
+            _ => {
+                let state = Arc::clone(&self.state);
+                into_future_trait(self.bf.get_streaming_credentials().map(move |credential| {
+                    state.lock().unwrap().credential = Some(credential.clone());
+                    credential
+                }))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spool_dir() -> PathBuf {
+        std::env::temp_dir().join(format!("bf-spool-{}", rand::random::<u64>()))
+    }
+
+    fn package_id() -> PackageId {
+        PackageId::new("N:package:1")
+    }
+
+    fn state(dir: &Path) -> WriterState {
+        WriterState {
+            spool: Spool::open(dir, &package_id()).unwrap(),
+            buffers: HashMap::new(),
+            credential: None,
+            send_error: None,
+        }
+    }
+
+    #[test]
+    fn samples_are_cut_into_numbered_blocks() {
+        let dir = spool_dir();
+        let mut state = state(&dir);
+        let channel_id = ChannelId::new("N:channel:1");
+        let samples: Vec<(i64, f64)> = (0..5).map(|i| (i * 1000, i as f64)).collect();
+
+        assert_eq!(state.push(&channel_id, &samples, 2).unwrap(), 2);
+        state.drain().unwrap();
+
+        let blocks = state.spool.pending().unwrap();
+        assert_eq!(
+            blocks.iter().map(|b| b.sequence()).collect::<Vec<_>>(),
+            vec![0, 1, 2]
+        );
+        assert_eq!(
+            blocks.iter().map(|b| b.samples().len()).collect::<Vec<_>>(),
+            vec![2, 2, 1]
+        );
+        assert_eq!(blocks[2].samples().values(), &vec![4.0]);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn spooled_blocks_survive_reopening() {
+        let dir = spool_dir();
+        let channel_id = ChannelId::new("N:channel:1");
+
+        {
+            let mut spool = Spool::open(&dir, &package_id()).unwrap();
+            for i in 0..3 {
+                spool
+                    .append(SampleBlock::new(channel_id.clone(), vec![i], vec![0.0]))
+                    .unwrap();
+            }
+            spool.remove(0).unwrap();
+        }
+
+        let mut spool = Spool::open(&dir, &package_id()).unwrap();
+        assert_eq!(spool.len(), 2);
+        spool.remove(1).unwrap();
+        spool.remove(1).unwrap();
+        assert_eq!(spool.len(), 1);
+        assert_eq!(spool.next_sequence(), 3);
+        assert_eq!(
+            spool
+                .append(SampleBlock::new(channel_id, vec![3], vec![0.0]))
+                .unwrap()
+                .sequence(),
+            3
+        );
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn spooled_blocks_belong_to_one_package() {
+        let dir = spool_dir();
+        let other = PackageId::new("N:package:2");
+
+        let mut spool = Spool::open(&dir, &package_id()).unwrap();
+        spool
+            .append(SampleBlock::new(
+                ChannelId::new("N:channel:1"),
+                vec![0],
+                vec![0.0],
+            ))
+            .unwrap();
+        match Spool::open(&dir, &other).unwrap_err().kind() {
+            ErrorKind::InvalidArguments { message } => assert!(message.contains("N:package:1")),
+            kind => panic!("unexpected error: {:?}", kind),
+        }
+
+        // Once every block is sent, the spool can be used for another
+        // package:
+        spool.remove(0).unwrap();
+        Spool::open(&dir, &other).unwrap();
+        assert!(Spool::open(&dir, &package_id()).is_ok());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -8,7 +8,17 @@ pub mod response;
 
 use std::borrow::Borrow;
 
-pub use self::client::progress::{ProgressCallback, ProgressUpdate};
+pub use self::client::cache::{CacheOptions, CacheStats, CachedResource};
+pub use self::client::cancellation::CancellationToken;
+pub use self::client::progress::{
+    ProgressAggregator, ProgressCallback, ProgressEvent, ProgressSummary, ProgressUpdate,
+};
+pub use self::client::report::{FileReport, FileStatus, UploadReport};
+pub use self::client::schema::{schema_changes, RecordSchema};
+pub use self::client::streaming::{
+    Spool, StreamingOptions, TimeseriesWriter, DEFAULT_MAX_PENDING_BLOCKS,
+    DEFAULT_STREAM_BLOCK_SIZE,
+};
 
 pub use self::client::Blackfynn;
 
@@ -21,3 +21,16 @@ impl ApiLogin {
         }
     }
 }
+
+/// A request to create an API key.
+#[derive(Clone, Hash, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateApiKey {
+    name: String,
+}
+
+impl CreateApiKey {
+    pub fn new<N: Into<String>>(name: N) -> Self {
+        Self { name: name.into() }
+    }
+}
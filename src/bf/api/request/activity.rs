@@ -0,0 +1,80 @@
+// Copyright (c) 2018 Blackfynn, Inc. All Rights Reserved.
+use chrono::{DateTime, Utc};
+
+use crate::bf::model::{ActivityEventType, UserId};
+
+/// Narrow down the activity history of a dataset. Events must match every
+/// criterion set.
+#[derive(Clone, Debug, Default, Hash, PartialEq, Eq)]
+pub struct ActivityFilter {
+    user_id: Option<UserId>,
+    event_types: Vec<ActivityEventType>,
+    after: Option<DateTime<Utc>>,
+    before: Option<DateTime<Utc>>,
+}
+
+impl ActivityFilter {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Only include events caused by a user.
+    pub fn with_user(mut self, user_id: UserId) -> Self {
+        self.user_id = Some(user_id);
+        self
+    }
+
+    /// Only include events of the given type. Can be repeated to include
+    /// several types.
+    pub fn with_event_type(mut self, event_type: ActivityEventType) -> Self {
+        self.event_types.push(event_type);
+        self
+    }
+
+    /// Only include events that happened at or after `after`.
+    pub fn with_after(mut self, after: DateTime<Utc>) -> Self {
+        self.after = Some(after);
+        self
+    }
+
+    /// Only include events that happened before `before`.
+    pub fn with_before(mut self, before: DateTime<Utc>) -> Self {
+        self.before = Some(before);
+        self
+    }
+
+    /// The query parameters of the filter.
+    pub fn params(&self) -> Vec<(String, String)> {
+        let mut params = vec![];
+        if let Some(ref user_id) = self.user_id {
+            params.push(("userId".to_string(), String::from(user_id)));
+        }
+        if !self.event_types.is_empty() {
+            let event_types: Vec<&str> = self.event_types.iter().map(|t| t.as_str()).collect();
+            params.push(("eventType".to_string(), event_types.join(",")));
+        }
+        if let Some(after) = self.after {
+            params.push(("after".to_string(), after.to_rfc3339()));
+        }
+        if let Some(before) = self.before {
+            params.push(("before".to_string(), before.to_rfc3339()));
+        }
+        params
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unset_criteria_are_left_out() {
+        let filter = ActivityFilter::new()
+            .with_event_type(ActivityEventType::Move)
+            .with_event_type(ActivityEventType::Rename);
+        assert_eq!(
+            filter.params(),
+            vec![("eventType".to_string(), "MOVE,RENAME".to_string())]
+        );
+    }
+}
@@ -0,0 +1,169 @@
+// Copyright (c) 2018 Blackfynn, Inc. All Rights Reserved.
+use std::io;
+
+use serde_derive::{Deserialize, Serialize};
+
+use crate::bf::model::ChannelId;
+use crate::bf::{Error, Result};
+
+/// Create an annotation layer on a timeseries package.
+#[derive(Clone, Debug, Hash, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnnotationLayer {
+    name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    color: Option<String>,
+}
+
+impl AnnotationLayer {
+    pub fn new<N: Into<String>>(name: N) -> Self {
+        Self {
+            name: name.into(),
+            description: None,
+            color: None,
+        }
+    }
+
+    pub fn with_description<D: Into<String>>(mut self, description: D) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    pub fn with_color<C: Into<String>>(mut self, color: C) -> Self {
+        self.color = Some(color.into());
+        self
+    }
+}
+
+/// Create or replace an annotation within a layer. `start` and `end` are
+/// in microseconds.
+#[derive(Clone, Debug, Hash, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Annotation {
+    channel_ids: Vec<ChannelId>,
+    start: i64,
+    end: i64,
+    label: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+}
+
+// A row of an annotation CSV file. See `Annotation::from_csv`.
+#[derive(Deserialize)]
+struct AnnotationRow {
+    label: String,
+    start: i64,
+    end: i64,
+    channels: String,
+    #[serde(default)]
+    description: Option<String>,
+}
+
+impl Annotation {
+    pub fn new<L: Into<String>>(
+        channel_ids: Vec<ChannelId>,
+        start: i64,
+        end: i64,
+        label: L,
+    ) -> Self {
+        Self {
+            channel_ids,
+            start,
+            end,
+            label: label.into(),
+            description: None,
+        }
+    }
+
+    pub fn with_description<D: Into<String>>(mut self, description: D) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    pub fn channel_ids(&self) -> &Vec<ChannelId> {
+        &self.channel_ids
+    }
+
+    pub fn start(&self) -> i64 {
+        self.start
+    }
+
+    pub fn end(&self) -> i64 {
+        self.end
+    }
+
+    pub fn label(&self) -> &String {
+        &self.label
+    }
+
+    /// Read annotations from CSV data with a header row naming the columns
+    /// `label`, `start`, `end`, `channels` and, optionally, `description`.
+    /// Channel ids are separated by `;`.
+    pub fn from_csv<R: io::Read>(reader: R) -> Result<Vec<Self>> {
+        let mut reader = csv::ReaderBuilder::new()
+            .trim(csv::Trim::All)
+            .from_reader(reader);
+        reader
+            .deserialize()
+            .enumerate()
+            .map(|(i, row)| {
+                let row: AnnotationRow = row?;
+                if row.start > row.end {
+                    return Err(Error::invalid_arguments(format!(
+                        "annotation on row {} starts after it ends",
+                        i + 1
+                    )));
+                }
+                let channel_ids = row
+                    .channels
+                    .split(';')
+                    .map(str::trim)
+                    .filter(|id| !id.is_empty())
+                    .map(ChannelId::from)
+                    .collect();
+                let annotation = Self::new(channel_ids, row.start, row.end, row.label);
+                Ok(match row.description {
+                    Some(ref description) if !description.is_empty() => {
+                        annotation.with_description(description.as_str())
+                    }
+                    _ => annotation,
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn annotations_are_read_from_csv() {
+        let data = "label,start,end,channels,description\n\
+                    seizure,1000,2000,N:channel:1;N:channel:2,onset\n\
+                    artifact,3000,3500,N:channel:1,\n";
+
+        let annotations = Annotation::from_csv(data.as_bytes()).unwrap();
+        assert_eq!(
+            annotations,
+            vec![
+                Annotation::new(
+                    vec!["N:channel:1".into(), "N:channel:2".into()],
+                    1000,
+                    2000,
+                    "seizure"
+                )
+                .with_description("onset"),
+                Annotation::new(vec!["N:channel:1".into()], 3000, 3500, "artifact"),
+            ]
+        );
+    }
+
+    #[test]
+    fn inverted_ranges_are_rejected() {
+        let data = "label,start,end,channels\nseizure,2000,1000,N:channel:1\n";
+        assert!(Annotation::from_csv(data.as_bytes()).is_err());
+    }
+}
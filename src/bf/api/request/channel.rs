@@ -0,0 +1,48 @@
+// Copyright (c) 2018 Blackfynn, Inc. All Rights Reserved.
+use serde_derive::Serialize;
+
+/// Create a channel on a timeseries package. `start` and `end` are in
+/// microseconds.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Channel {
+    name: String,
+    rate: f64,
+    start: i64,
+    end: i64,
+    unit: String,
+    channel_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    group: Option<String>,
+}
+
+impl Channel {
+    /// A continuous channel sampled at `rate` Hz, in microvolts, whose
+    /// recording starts at `start`.
+    pub fn new<N: Into<String>>(name: N, rate: f64, start: i64) -> Self {
+        Self {
+            name: name.into(),
+            rate,
+            start,
+            end: start,
+            unit: "uV".to_string(),
+            channel_type: "CONTINUOUS".to_string(),
+            group: None,
+        }
+    }
+
+    pub fn with_unit<U: Into<String>>(mut self, unit: U) -> Self {
+        self.unit = unit.into();
+        self
+    }
+
+    pub fn with_channel_type<T: Into<String>>(mut self, channel_type: T) -> Self {
+        self.channel_type = channel_type.into();
+        self
+    }
+
+    pub fn with_group<G: Into<String>>(mut self, group: G) -> Self {
+        self.group = Some(group.into());
+        self
+    }
+}
@@ -1,7 +1,10 @@
 // Copyright (c) 2018 Blackfynn, Inc. All Rights Reserved.
+use std::collections::{BTreeMap, HashMap};
 use std::fs::File;
 use std::io::{self, Read, Seek, SeekFrom};
 use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::{cmp, fs};
 
 use futures::Async::Ready;
 use sha2::{Digest, Sha256};
@@ -13,14 +16,101 @@ use crate::bf::model::upload::Checksum;
 use crate::bf::model::ImportId;
 
 // 5MiB (the minimum part size for s3 multipart requests)
-const DEFAULT_CHUNK_SIZE_BYTES: u64 = 5_242_880;
+pub const DEFAULT_CHUNK_SIZE_BYTES: u64 = 5_242_880;
 
 // SHA256 hash of an empty byte array
 const EMPTY_SHA256_HASH: &str = "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855";
 
+fn sha256_checksum(bytes: &[u8]) -> Checksum {
+    let mut sha256_hasher = Sha256::new();
+    sha256_hasher.input(bytes);
+    Checksum(format!("{:x}", sha256_hasher.result()))
+}
+
+/// The per-chunk checksums of the files sent as part of an upload,
+/// keyed by the path of each file relative to the upload, as given by
+/// `S3File::relative_path`.
+///
+/// Checksums are recorded by `ChunkedFilePayload` as chunks are
+/// streamed, so the whole-file hash can be computed without reading
+/// the file a second time. Cloning shares the underlying record.
+#[derive(Clone, Debug, Default)]
+pub struct FileChecksums {
+    inner: Arc<Mutex<HashMap<String, BTreeMap<usize, Checksum>>>>,
+}
+
+impl FileChecksums {
+    fn record(&self, key: &str, chunk_number: usize, checksum: &Checksum) {
+        self.inner
+            .lock()
+            .unwrap()
+            .entry(key.to_string())
+            .or_default()
+            .insert(chunk_number, checksum.clone());
+    }
+
+    /// Returns true if the chunk of a file was streamed through a
+    /// `ChunkedFilePayload` sharing this record.
+    pub fn was_streamed(&self, key: &str, chunk_number: usize) -> bool {
+        self.inner
+            .lock()
+            .unwrap()
+            .get(key)
+            .is_some_and(|chunks| chunks.contains_key(&chunk_number))
+    }
+
+    /// Compute the hash of a file in the scheme used by the upload
+    /// service, which is the SHA-256 digest of the hex-encoded chunk
+    /// checksums concatenated in chunk order.
+    ///
+    /// Any chunks that were not streamed through a `ChunkedFilePayload`
+    /// sharing this record, such as the parts already received by the
+    /// service before a resumed upload, are read from disk.
+    pub fn file_hash<P: AsRef<Path>>(
+        &self,
+        key: &str,
+        file_path: P,
+        chunk_size_bytes: u64,
+    ) -> io::Result<Checksum> {
+        let file_path = file_path.as_ref();
+        let file_size = fs::metadata(file_path)?.len();
+        let total_parts = cmp::max(
+            1,
+            (file_size as f64 / chunk_size_bytes as f64).ceil() as usize,
+        );
+
+        let recorded = self
+            .inner
+            .lock()
+            .unwrap()
+            .get(key)
+            .cloned()
+            .unwrap_or_default();
+
+        let mut file = None;
+        let mut combined = String::new();
+        for chunk_number in 0..total_parts {
+            match recorded.get(&chunk_number) {
+                Some(checksum) => combined.push_str(&checksum.0),
+                None => {
+                    if file.is_none() {
+                        file = Some(File::open(file_path)?);
+                    }
+                    let file = file.as_mut().unwrap();
+                    let mut buffer = vec![];
+                    file.seek(SeekFrom::Start(chunk_number as u64 * chunk_size_bytes))?;
+                    file.take(chunk_size_bytes).read_to_end(&mut buffer)?;
+                    combined.push_str(&sha256_checksum(&buffer).0);
+                }
+            }
+        }
+
+        Ok(sha256_checksum(combined.as_bytes()))
+    }
+}
+
 pub struct ChunkedFilePayload {
     import_id: ImportId,
-    file_path: PathBuf,
     file: File,
     chunk_size_bytes: u64,
     bytes_sent: u64,
@@ -28,6 +118,9 @@ pub struct ChunkedFilePayload {
     parts_sent: usize,
     expected_total_parts: Option<usize>,
     missing_parts: Vec<usize>,
+    checksums: FileChecksums,
+    checksum_key: String,
+    progress_path: PathBuf,
 }
 
 pub struct FileChunk {
@@ -71,6 +164,10 @@ impl ChunkedFilePayload {
         sorted_missing_parts.sort_unstable();
 
         let file_path = file_path.as_ref().to_path_buf();
+        let file_name = file_path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
 
         let file = File::open(file_path.clone()).unwrap();
         let file_size = file.metadata().unwrap().len();
@@ -104,7 +201,7 @@ impl ChunkedFilePayload {
 
         Self {
             import_id,
-            file_path,
+            progress_path: file_path,
             file,
             chunk_size_bytes,
             bytes_sent,
@@ -112,14 +209,31 @@ impl ChunkedFilePayload {
             parts_sent,
             expected_total_parts,
             missing_parts: sorted_missing_parts,
+            checksums: Default::default(),
+            checksum_key: file_name,
         }
     }
 
+    /// Record the checksum of every chunk produced by this payload in
+    /// the given `FileChecksums`, under `key`.
+    pub fn with_checksums<K: Into<String>>(mut self, checksums: FileChecksums, key: K) -> Self {
+        self.checksums = checksums;
+        self.checksum_key = key.into();
+        self
+    }
+
+    /// Report progress under `path`, rather than the path the file is
+    /// read from.
+    pub fn with_progress_path<P: AsRef<Path>>(mut self, path: P) -> Self {
+        self.progress_path = path.as_ref().to_path_buf();
+        self
+    }
+
     fn build_progress_update(&self, done: bool) -> ProgressUpdate {
         ProgressUpdate::new(
             self.parts_sent,
             self.import_id.clone(),
-            self.file_path.clone(),
+            self.progress_path.clone(),
             self.bytes_sent,
             self.file_size,
             done,
@@ -141,10 +255,12 @@ impl Stream for ChunkedFilePayload {
             // send a single element with an empty buffer
             if self.parts_sent == 0 {
                 self.parts_sent += 1;
+                let checksum = Checksum(String::from(EMPTY_SHA256_HASH));
+                self.checksums.record(&self.checksum_key, 0, &checksum);
                 Ok(Ready(Some((
                     FileChunk {
                         bytes: vec![],
-                        checksum: Checksum(String::from(EMPTY_SHA256_HASH)),
+                        checksum,
                         chunk_number: 0,
                     },
                     self.build_progress_update(true),
@@ -185,15 +301,19 @@ impl Stream for ChunkedFilePayload {
 
                         buffer.truncate(bytes_read);
 
-                        let mut sha256_hasher = Sha256::new();
-                        sha256_hasher.input(&buffer);
+                        let checksum = sha256_checksum(&buffer);
+                        self.checksums.record(
+                            &self.checksum_key,
+                            seek_from_chunk_number,
+                            &checksum,
+                        );
 
                         self.parts_sent += 1;
 
                         Ready(Some((
                             FileChunk {
                                 bytes: buffer,
-                                checksum: Checksum(format!("{:x}", sha256_hasher.result())),
+                                checksum,
                                 chunk_number: seek_from_chunk_number,
                             },
                             self.build_progress_update(self.all_parts_sent()),
@@ -392,4 +512,107 @@ mod tests {
         assert_eq!(progress.percent_done(), 100 as f32);
         assert_eq!(progress.is_done(), true);
     }
+
+    fn small_test_file_path() -> PathBuf {
+        concat!(env!("CARGO_MANIFEST_DIR"), "/test/data/small/example.csv").into()
+    }
+
+    #[test]
+    fn file_hash_from_streamed_chunks_matches_file_hash_read_from_disk() {
+        let checksums = FileChecksums::default();
+        let mut payload = ChunkedFilePayload::new_with_chunk_size(
+            ImportId::new("import id"),
+            small_test_file_path(),
+            100,
+            None,
+        )
+        .with_checksums(checksums.clone(), "example.csv");
+        let sent = chunks(payload.by_ref());
+
+        let streamed = checksums
+            .file_hash("example.csv", small_test_file_path(), 100)
+            .unwrap();
+        let from_disk = FileChecksums::default()
+            .file_hash("example.csv", small_test_file_path(), 100)
+            .unwrap();
+
+        assert_eq!(streamed, from_disk);
+
+        let combined: String = sent.iter().map(|c| c.checksum.0.clone()).collect();
+        assert_eq!(streamed, sha256_checksum(combined.as_bytes()));
+    }
+
+    #[test]
+    fn file_hash_of_resumed_upload_includes_previously_sent_chunks() {
+        let missing_parts = FileMissingParts {
+            file_name: "example.csv".to_string(),
+            missing_parts: vec![2, 5],
+            expected_total_parts: 12,
+        };
+        let checksums = FileChecksums::default();
+        let mut payload = ChunkedFilePayload::new_with_chunk_size(
+            ImportId::new("import id"),
+            small_test_file_path(),
+            100,
+            Some(&missing_parts),
+        )
+        .with_checksums(checksums.clone(), "example.csv");
+        assert_eq!(chunks(payload.by_ref()).len(), 2);
+
+        assert_eq!(
+            checksums
+                .file_hash("example.csv", small_test_file_path(), 100)
+                .unwrap(),
+            FileChecksums::default()
+                .file_hash("example.csv", small_test_file_path(), 100)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn files_with_the_same_name_keep_separate_checksums() {
+        let checksums = FileChecksums::default();
+        let other_file_path: PathBuf =
+            concat!(env!("CARGO_MANIFEST_DIR"), "/test/data/small/empty_file").into();
+        for (key, path) in &[
+            ("a/data", small_test_file_path()),
+            ("b/data", other_file_path.clone()),
+        ] {
+            let mut payload = ChunkedFilePayload::new_with_chunk_size(
+                ImportId::new("import id"),
+                path,
+                100,
+                None,
+            )
+            .with_checksums(checksums.clone(), *key);
+            chunks(payload.by_ref());
+        }
+
+        assert_eq!(
+            checksums
+                .file_hash("a/data", small_test_file_path(), 100)
+                .unwrap(),
+            FileChecksums::default()
+                .file_hash("a/data", small_test_file_path(), 100)
+                .unwrap()
+        );
+        assert_eq!(
+            checksums
+                .file_hash("b/data", &other_file_path, 100)
+                .unwrap(),
+            sha256_checksum(EMPTY_SHA256_HASH.as_bytes())
+        );
+    }
+
+    #[test]
+    fn file_hash_of_zero_byte_file_uses_the_empty_chunk_checksum() {
+        let hash = FileChecksums::default()
+            .file_hash(
+                "empty_file",
+                concat!(env!("CARGO_MANIFEST_DIR"), "/test/data/small/empty_file"),
+                DEFAULT_CHUNK_SIZE_BYTES,
+            )
+            .unwrap();
+        assert_eq!(hash, sha256_checksum(EMPTY_SHA256_HASH.as_bytes()));
+    }
 }
@@ -0,0 +1,68 @@
+// Copyright (c) 2018 Blackfynn, Inc. All Rights Reserved.
+use serde_derive::Serialize;
+
+use crate::bf::model::Role;
+
+/// Share a dataset with several users or teams.
+#[derive(Clone, Hash, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Share {
+    ids: Vec<String>,
+    role: Role,
+}
+
+impl Share {
+    pub fn new<I>(ids: Vec<I>, role: Role) -> Self
+    where
+        I: Into<String>,
+    {
+        Self {
+            ids: ids.into_iter().map(Into::into).collect(),
+            role,
+        }
+    }
+}
+
+/// Stop sharing a dataset with several users or teams.
+#[derive(Clone, Hash, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Unshare {
+    ids: Vec<String>,
+}
+
+impl Unshare {
+    pub fn new<I>(ids: Vec<I>) -> Self
+    where
+        I: Into<String>,
+    {
+        Self {
+            ids: ids.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+/// Share a dataset with every member of the organization.
+#[derive(Clone, Hash, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrganizationRole {
+    role: Role,
+}
+
+impl OrganizationRole {
+    pub fn new(role: Role) -> Self {
+        Self { role }
+    }
+}
+
+/// Transfer the ownership of a dataset to another user.
+#[derive(Clone, Hash, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransferOwnership {
+    id: String,
+}
+
+impl TransferOwnership {
+    pub fn new<I: Into<String>>(id: I) -> Self {
+        Self { id: id.into() }
+    }
+}
@@ -0,0 +1,338 @@
+// Copyright (c) 2018 Blackfynn, Inc. All Rights Reserved.
+use serde_derive::Serialize;
+
+use crate::bf::model::{ConceptProperty, PackageId, PropertyValue, RecordId, RecordValue};
+use crate::bf::{Error, Result};
+
+/// The type of relationship linking records to the packages they belong
+/// to.
+pub const BELONGS_TO: &str = "belongs_to";
+
+/// Create a metadata concept, or update its name and description.
+#[derive(Clone, Debug, Hash, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Concept {
+    name: String,
+    display_name: String,
+    description: String,
+}
+
+impl Concept {
+    pub fn new<N: Into<String>>(name: N) -> Self {
+        let name = name.into();
+        Self {
+            display_name: name.clone(),
+            name,
+            description: String::new(),
+        }
+    }
+
+    pub fn with_display_name<D: Into<String>>(mut self, display_name: D) -> Self {
+        self.display_name = display_name.into();
+        self
+    }
+
+    pub fn with_description<D: Into<String>>(mut self, description: D) -> Self {
+        self.description = description.into();
+        self
+    }
+}
+
+/// The values of a record to create, or to replace those of an existing
+/// record with.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Record {
+    values: Vec<RecordValue>,
+}
+
+impl Record {
+    pub fn new(values: Vec<RecordValue>) -> Self {
+        Self { values }
+    }
+
+    /// Set the value of a property.
+    pub fn with<N, V>(mut self, name: N, value: V) -> Self
+    where
+        N: Into<String>,
+        V: Into<PropertyValue>,
+    {
+        self.values.push(RecordValue::new(name, value));
+        self
+    }
+
+    pub fn values(&self) -> &Vec<RecordValue> {
+        &self.values
+    }
+
+    /// Check the record against the schema of a concept: every value must
+    /// name a property of the schema and be of its type, and every
+    /// required property must have a value.
+    pub fn validate(&self, schema: &[ConceptProperty]) -> Result<()> {
+        for value in &self.values {
+            match schema.iter().find(|p| p.name() == value.name()) {
+                None => {
+                    return Err(Error::invalid_arguments(format!(
+                        "no such property: {}",
+                        value.name()
+                    )))
+                }
+                Some(property) if !value.value().is_compatible_with(property.data_type()) => {
+                    return Err(Error::invalid_arguments(format!(
+                        "property {} expects a {} value, got {}",
+                        property.name(),
+                        property.data_type(),
+                        value.value().data_type()
+                    )))
+                }
+                Some(_) => {}
+            }
+        }
+
+        match schema
+            .iter()
+            .filter(|p| p.is_required())
+            .find(|p| !self.values.iter().any(|v| v.name() == p.name()))
+        {
+            Some(property) => Err(Error::invalid_arguments(format!(
+                "missing required property: {}",
+                property.name()
+            ))),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Delete several records.
+#[derive(Clone, Debug, Hash, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeleteRecords {
+    ids: Vec<RecordId>,
+}
+
+impl DeleteRecords {
+    pub fn new(ids: Vec<RecordId>) -> Self {
+        Self { ids }
+    }
+}
+
+/// How a record property is compared to a value in a `RecordQuery`.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum FilterOperator {
+    Eq,
+    Neq,
+    Lt,
+    Lte,
+    Gt,
+    Gte,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RecordFilter {
+    property: String,
+    operator: FilterOperator,
+    value: PropertyValue,
+}
+
+/// Find the records of a concept whose properties match every filter.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordQuery {
+    filters: Vec<RecordFilter>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    limit: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    offset: Option<u32>,
+}
+
+impl RecordQuery {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Only match records whose property compares to `value`.
+    pub fn filter<P, V>(mut self, property: P, operator: FilterOperator, value: V) -> Self
+    where
+        P: Into<String>,
+        V: Into<PropertyValue>,
+    {
+        self.filters.push(RecordFilter {
+            property: property.into(),
+            operator,
+            value: value.into(),
+        });
+        self
+    }
+
+    /// Only match records whose property equals `value`.
+    pub fn filter_eq<P, V>(self, property: P, value: V) -> Self
+    where
+        P: Into<String>,
+        V: Into<PropertyValue>,
+    {
+        self.filter(property, FilterOperator::Eq, value)
+    }
+
+    pub fn with_limit(mut self, limit: u32) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    pub fn with_offset(mut self, offset: u32) -> Self {
+        self.offset = Some(offset);
+        self
+    }
+}
+
+/// Create a type of relationship between records.
+#[derive(Clone, Debug, Hash, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RelationshipType {
+    name: String,
+    display_name: String,
+    description: String,
+    schema: Vec<ConceptProperty>,
+}
+
+impl RelationshipType {
+    pub fn new<N: Into<String>>(name: N) -> Self {
+        let name = name.into();
+        Self {
+            display_name: name.clone(),
+            name,
+            description: String::new(),
+            schema: vec![],
+        }
+    }
+
+    pub fn with_display_name<D: Into<String>>(mut self, display_name: D) -> Self {
+        self.display_name = display_name.into();
+        self
+    }
+
+    pub fn with_description<D: Into<String>>(mut self, description: D) -> Self {
+        self.description = description.into();
+        self
+    }
+}
+
+/// Relate one record to another.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordLink {
+    from: RecordId,
+    to: RecordId,
+    values: Vec<RecordValue>,
+}
+
+impl RecordLink {
+    pub fn new(from: RecordId, to: RecordId) -> Self {
+        Self {
+            from,
+            to,
+            values: vec![],
+        }
+    }
+}
+
+/// Delete several relationships between records.
+#[derive(Clone, Debug, Hash, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeleteRelationships {
+    ids: Vec<String>,
+}
+
+impl DeleteRelationships {
+    pub fn new(ids: Vec<String>) -> Self {
+        Self { ids }
+    }
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq, Serialize)]
+struct ConceptInstance {
+    id: RecordId,
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq, Serialize)]
+struct LinkTarget {
+    #[serde(rename = "ConceptInstance")]
+    concept_instance: ConceptInstance,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PackageLinkTarget {
+    direction: String,
+    link_target: LinkTarget,
+    relationship_type: String,
+    relationship_data: Vec<RecordValue>,
+}
+
+/// Link records to a package.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PackageLink {
+    external_id: PackageId,
+    targets: Vec<PackageLinkTarget>,
+}
+
+impl PackageLink {
+    pub fn new<R: Into<String>>(
+        package_id: PackageId,
+        record_ids: Vec<RecordId>,
+        relationship_type: R,
+    ) -> Self {
+        let relationship_type = relationship_type.into();
+        Self {
+            external_id: package_id,
+            targets: record_ids
+                .into_iter()
+                .map(|id| PackageLinkTarget {
+                    direction: "FromTarget".to_string(),
+                    link_target: LinkTarget {
+                        concept_instance: ConceptInstance { id },
+                    },
+                    relationship_type: relationship_type.clone(),
+                    relationship_data: vec![],
+                })
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bf::model::PropertyType;
+
+    #[test]
+    fn records_are_validated_against_the_schema() {
+        let schema = vec![
+            ConceptProperty::new("name", PropertyType::String).with_title(true),
+            ConceptProperty::new("age", PropertyType::Long),
+            ConceptProperty::new("weight", PropertyType::Double),
+        ];
+
+        assert!(Record::new(vec![])
+            .with("name", "subject 1")
+            .with("weight", 62)
+            .validate(&schema)
+            .is_ok());
+        assert!(Record::new(vec![])
+            .with("age", 31)
+            .validate(&schema)
+            .is_err());
+        assert!(Record::new(vec![])
+            .with("name", "subject 1")
+            .with("age", "31")
+            .validate(&schema)
+            .is_err());
+        assert!(Record::new(vec![])
+            .with("name", "subject 1")
+            .with("height", 1.7)
+            .validate(&schema)
+            .is_err());
+    }
+}
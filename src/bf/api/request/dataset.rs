@@ -1,12 +1,21 @@
 // Copyright (c) 2018 Blackfynn, Inc. All Rights Reserved.
+use chrono::NaiveDate;
 use serde_derive::Serialize;
 
+use crate::bf::model::{Contributor, DatasetStatus, PublicationType};
+
 #[derive(Clone, Hash, PartialEq, Eq, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Create {
     name: String,
     description: Option<String>,
     automatically_process_packages: bool,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    tags: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    license: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    contributors: Vec<Contributor>,
 }
 
 impl Create {
@@ -19,6 +28,9 @@ impl Create {
             name: name.into(),
             description: description.map(Into::into),
             automatically_process_packages: false,
+            tags: vec![],
+            license: None,
+            contributors: vec![],
         }
     }
 
@@ -29,13 +41,40 @@ impl Create {
         self.automatically_process_packages = automatically_process_packages;
         self
     }
+
+    pub fn with_tags(mut self, tags: Vec<String>) -> Self {
+        self.tags = tags;
+        self
+    }
+
+    pub fn with_license<L: Into<String>>(mut self, license: L) -> Self {
+        self.license = Some(license.into());
+        self
+    }
+
+    pub fn with_contributors(mut self, contributors: Vec<Contributor>) -> Self {
+        self.contributors = contributors;
+        self
+    }
 }
 
-#[derive(Clone, Hash, PartialEq, Eq, Serialize)]
+/// A partial update of a dataset. Fields that are not set are left as
+/// they are.
+#[derive(Clone, Default, Hash, PartialEq, Eq, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Update {
-    name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tags: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    license: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    contributors: Option<Vec<Contributor>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    status: Option<DatasetStatus>,
 }
 
 impl Update {
@@ -45,8 +84,121 @@ impl Update {
         Q: Into<String>,
     {
         Self {
-            name: name.into(),
+            name: Some(name.into()),
             description: description.map(Into::into),
+            ..Default::default()
         }
     }
+
+    pub fn with_name<N: Into<String>>(mut self, name: N) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    pub fn with_description<D: Into<String>>(mut self, description: D) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Replace the tags of the dataset.
+    pub fn with_tags(mut self, tags: Vec<String>) -> Self {
+        self.tags = Some(tags);
+        self
+    }
+
+    pub fn with_license<L: Into<String>>(mut self, license: L) -> Self {
+        self.license = Some(license.into());
+        self
+    }
+
+    /// Replace the contributors of the dataset.
+    pub fn with_contributors(mut self, contributors: Vec<Contributor>) -> Self {
+        self.contributors = Some(contributors);
+        self
+    }
+
+    pub fn with_status(mut self, status: DatasetStatus) -> Self {
+        self.status = Some(status);
+        self
+    }
+}
+
+/// Replace the README of a dataset.
+#[derive(Clone, Hash, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Readme {
+    readme: String,
+}
+
+impl Readme {
+    pub fn new<R: Into<String>>(readme: R) -> Self {
+        Self {
+            readme: readme.into(),
+        }
+    }
+}
+
+/// Request the publication of a dataset.
+#[derive(Clone, Hash, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Publication {
+    publication_type: PublicationType,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    embargo_release_date: Option<NaiveDate>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    comments: Option<String>,
+}
+
+impl Publication {
+    /// Publish the dataset once the request is accepted.
+    pub fn new() -> Self {
+        Self {
+            publication_type: PublicationType::Publication,
+            embargo_release_date: None,
+            comments: None,
+        }
+    }
+
+    /// Publish the dataset once the request is accepted, keeping its files
+    /// private until `release_date`.
+    pub fn embargo(release_date: NaiveDate) -> Self {
+        Self {
+            publication_type: PublicationType::Embargo,
+            embargo_release_date: Some(release_date),
+            comments: None,
+        }
+    }
+
+    pub fn with_comments<C: Into<String>>(mut self, comments: C) -> Self {
+        self.comments = Some(comments.into());
+        self
+    }
+
+    pub fn publication_type(&self) -> PublicationType {
+        self.publication_type
+    }
+
+    pub fn embargo_release_date(&self) -> Option<&NaiveDate> {
+        self.embargo_release_date.as_ref()
+    }
+}
+
+impl Default for Publication {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn partial_updates_only_include_set_fields() {
+        let update = Update::default().with_tags(vec!["eeg".to_string()]);
+        assert_eq!(
+            serde_json::to_string(&update).unwrap(),
+            r#"{"tags":["eeg"]}"#
+        );
+    }
 }
@@ -0,0 +1,21 @@
+// Copyright (c) 2018 Blackfynn, Inc. All Rights Reserved.
+use serde_derive::Serialize;
+
+use crate::bf::model::PackageId;
+
+#[derive(Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Delete {
+    things: Vec<PackageId>,
+}
+
+impl Delete {
+    pub fn new<T>(things: Vec<T>) -> Self
+    where
+        T: Into<PackageId>,
+    {
+        Self {
+            things: things.into_iter().map(Into::into).collect::<Vec<_>>(),
+        }
+    }
+}
@@ -3,14 +3,23 @@
 //! Client request types to the Blackfynn API.
 
 mod account;
+pub mod activity;
+pub mod annotation;
+pub mod channel;
 pub mod chunked_http;
+pub mod collaborator;
+pub mod concept;
 pub mod dataset;
+pub mod delete;
 pub mod mv;
+pub mod organization;
 pub mod package;
+pub mod search;
+pub mod team;
 mod upload;
 mod user;
 
 // Re-export:
-pub use self::account::ApiLogin;
+pub use self::account::{ApiLogin, CreateApiKey};
 pub use self::upload::UploadPreview;
-pub use self::user::User;
+pub use self::user::{PasswordChange, User, UserUpdate};
@@ -0,0 +1,56 @@
+// Copyright (c) 2018 Blackfynn, Inc. All Rights Reserved.
+use serde_derive::Serialize;
+
+use crate::bf::model::Role;
+
+/// An invitation for someone to join an organization.
+#[derive(Clone, Debug, Hash, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Invite {
+    email: String,
+    first_name: String,
+    last_name: String,
+    role: Role,
+}
+
+impl Invite {
+    pub fn new<E, F, L>(email: E, first_name: F, last_name: L, role: Role) -> Self
+    where
+        E: Into<String>,
+        F: Into<String>,
+        L: Into<String>,
+    {
+        Self {
+            email: email.into(),
+            first_name: first_name.into(),
+            last_name: last_name.into(),
+            role,
+        }
+    }
+}
+
+/// Invite several people to join an organization.
+#[derive(Clone, Hash, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Invites {
+    invites: Vec<Invite>,
+}
+
+impl Invites {
+    pub fn new(invites: Vec<Invite>) -> Self {
+        Self { invites }
+    }
+}
+
+/// Change the role of a member of an organization.
+#[derive(Clone, Hash, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateMember {
+    role: Role,
+}
+
+impl UpdateMember {
+    pub fn new(role: Role) -> Self {
+        Self { role }
+    }
+}
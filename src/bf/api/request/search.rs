@@ -0,0 +1,112 @@
+// Copyright (c) 2018 Blackfynn, Inc. All Rights Reserved.
+use chrono::{DateTime, Utc};
+use serde_derive::Serialize;
+
+use crate::bf::model::DatasetNodeId;
+
+/// The number of hits returned per page, unless set otherwise.
+pub const DEFAULT_SEARCH_PAGE_SIZE: u32 = 25;
+
+/// A full-text search of the datasets and packages the user has access
+/// to, narrowed down by facets.
+#[derive(Clone, Debug, Hash, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Search {
+    query: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    datasets: Vec<DatasetNodeId>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    package_types: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    file_types: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    created_after: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    created_before: Option<DateTime<Utc>>,
+    limit: u32,
+    offset: u32,
+}
+
+impl Search {
+    pub fn new<Q: Into<String>>(query: Q) -> Self {
+        Self {
+            query: query.into(),
+            datasets: vec![],
+            package_types: vec![],
+            file_types: vec![],
+            created_after: None,
+            created_before: None,
+            limit: DEFAULT_SEARCH_PAGE_SIZE,
+            offset: 0,
+        }
+    }
+
+    /// Only search within a dataset. Can be repeated to search several
+    /// datasets.
+    pub fn with_dataset(mut self, dataset_id: DatasetNodeId) -> Self {
+        self.datasets.push(dataset_id);
+        self
+    }
+
+    /// Only include packages of a type (ex. "TimeSeries"). Can be repeated.
+    pub fn with_package_type<P: Into<String>>(mut self, package_type: P) -> Self {
+        self.package_types.push(package_type.into());
+        self
+    }
+
+    /// Only include packages with source files of a type (ex. "EDF"). Can
+    /// be repeated.
+    pub fn with_file_type<F: Into<String>>(mut self, file_type: F) -> Self {
+        self.file_types.push(file_type.into());
+        self
+    }
+
+    pub fn with_created_after(mut self, created_after: DateTime<Utc>) -> Self {
+        self.created_after = Some(created_after);
+        self
+    }
+
+    pub fn with_created_before(mut self, created_before: DateTime<Utc>) -> Self {
+        self.created_before = Some(created_before);
+        self
+    }
+
+    /// The number of hits per page.
+    pub fn with_limit(mut self, limit: u32) -> Self {
+        self.limit = limit.max(1);
+        self
+    }
+
+    /// The number of hits to skip.
+    pub fn with_offset(mut self, offset: u32) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    pub fn limit(&self) -> u32 {
+        self.limit
+    }
+
+    pub fn offset(&self) -> u32 {
+        self.offset
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unset_facets_are_left_out() {
+        let search = Search::new("seizure").with_package_type("TimeSeries");
+        assert_eq!(
+            serde_json::to_value(&search).unwrap(),
+            serde_json::json!({
+                "query": "seizure",
+                "packageTypes": ["TimeSeries"],
+                "limit": DEFAULT_SEARCH_PAGE_SIZE,
+                "offset": 0,
+            })
+        );
+    }
+}
@@ -0,0 +1,50 @@
+// Copyright (c) 2018 Blackfynn, Inc. All Rights Reserved.
+use serde_derive::Serialize;
+
+use crate::bf::model::UserId;
+
+/// Create a team, or rename an existing one.
+#[derive(Clone, Hash, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Team {
+    name: String,
+}
+
+impl Team {
+    pub fn new<N: Into<String>>(name: N) -> Self {
+        Self { name: name.into() }
+    }
+}
+
+/// Add several members to a team.
+#[derive(Clone, Hash, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AddMembers {
+    ids: Vec<UserId>,
+}
+
+impl AddMembers {
+    pub fn new(ids: Vec<UserId>) -> Self {
+        Self { ids }
+    }
+}
+
+/// Change the role of a member within a team.
+#[derive(Clone, Hash, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateMember {
+    team_role: String,
+}
+
+impl UpdateMember {
+    pub fn new(administrator: bool) -> Self {
+        Self {
+            team_role: if administrator {
+                "administrator"
+            } else {
+                "member"
+            }
+            .to_string(),
+        }
+    }
+}
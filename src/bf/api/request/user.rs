@@ -55,3 +55,108 @@ impl Default for User {
         }
     }
 }
+
+/// A partial update of a user profile, as sent by `Blackfynn::update_user`.
+///
+/// Only the fields that are set are sent. The preferred organization can
+/// also be cleared, which is sent as an explicit `null`.
+#[derive(Clone, Debug, Default, Hash, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserUpdate {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    organization: Option<Option<OrganizationId>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    email: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    color: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    last_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    first_name: Option<String>,
+}
+
+impl UserUpdate {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Set the preferred organization, or clear it with `None`.
+    pub fn with_organization(mut self, organization: Option<OrganizationId>) -> Self {
+        self.organization = Some(organization);
+        self
+    }
+
+    pub fn with_email<S: Into<String>>(mut self, email: S) -> Self {
+        self.email = Some(email.into());
+        self
+    }
+
+    pub fn with_url<S: Into<String>>(mut self, url: S) -> Self {
+        self.url = Some(url.into());
+        self
+    }
+
+    pub fn with_color<S: Into<String>>(mut self, color: S) -> Self {
+        self.color = Some(color.into());
+        self
+    }
+
+    pub fn with_last_name<S: Into<String>>(mut self, last_name: S) -> Self {
+        self.last_name = Some(last_name.into());
+        self
+    }
+
+    pub fn with_first_name<S: Into<String>>(mut self, first_name: S) -> Self {
+        self.first_name = Some(first_name.into());
+        self
+    }
+}
+
+/// A password change HTTP `PUT` request. The current password must be
+/// given along with the new one.
+#[derive(Clone, Hash, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PasswordChange {
+    current_credential: String,
+    credential: String,
+}
+
+impl PasswordChange {
+    pub fn new<C, N>(current_password: C, new_password: N) -> Self
+    where
+        C: Into<String>,
+        N: Into<String>,
+    {
+        Self {
+            current_credential: current_password.into(),
+            credential: new_password.into(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_changed_fields_are_sent() {
+        let update = UserUpdate::new()
+            .with_first_name("Ada")
+            .with_color("#2760FF");
+        assert_eq!(
+            serde_json::to_string(&update).unwrap(),
+            r##"{"color":"#2760FF","firstName":"Ada"}"##
+        );
+    }
+
+    #[test]
+    fn cleared_organizations_are_sent_as_null() {
+        let update = UserUpdate::new().with_organization(None);
+        assert_eq!(
+            serde_json::to_string(&update).unwrap(),
+            r#"{"organization":null}"#
+        );
+    }
+}
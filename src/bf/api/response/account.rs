@@ -1,4 +1,6 @@
 // Copyright (c) 2018 Blackfynn, Inc. All Rights Reserved.
+use std::fmt;
+
 use serde_derive::Deserialize;
 
 use crate::bf::model;
@@ -24,3 +26,52 @@ impl ApiSession {
         self.expires_in
     }
 }
+
+/// An API key used to log in to the Blackfynn platform.
+#[derive(Clone, Hash, PartialEq, Eq, Deserialize)]
+pub struct ApiKey {
+    name: String,
+    key: String,
+    secret: Option<String>,
+}
+
+// The secret is never printed:
+impl fmt::Debug for ApiKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ApiKey")
+            .field("name", &self.name)
+            .field("key", &self.key)
+            .field("secret", &self.secret.as_ref().map(|_| "<redacted>"))
+            .finish()
+    }
+}
+
+impl ApiKey {
+    pub fn name(&self) -> &String {
+        &self.name
+    }
+
+    pub fn key(&self) -> &String {
+        &self.key
+    }
+
+    /// Get the secret of the key. The secret is only returned when the
+    /// key is created.
+    pub fn secret(&self) -> Option<&String> {
+        self.secret.as_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn api_key_secrets_are_not_printed() {
+        let api_key: ApiKey =
+            serde_json::from_str(r#"{"name": "ci", "key": "key", "secret": "hunter2"}"#).unwrap();
+        let printed = format!("{:?}", api_key);
+        assert!(!printed.contains("hunter2"));
+        assert!(printed.contains("<redacted>"));
+    }
+}
@@ -0,0 +1,28 @@
+// Copyright (c) 2018 Blackfynn, Inc. All Rights Reserved.
+
+use serde_derive::Deserialize;
+
+use crate::bf::model;
+
+/// A page of the activity history of a dataset, most recent first.
+#[derive(Debug, Clone, Eq, Hash, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActivityPage {
+    events: Vec<model::ActivityEvent>,
+    cursor: Option<String>,
+}
+
+impl ActivityPage {
+    pub fn events(&self) -> &Vec<model::ActivityEvent> {
+        &self.events
+    }
+
+    /// The cursor of the next page, if there is one.
+    pub fn cursor(&self) -> Option<&String> {
+        self.cursor.as_ref()
+    }
+
+    pub fn take(self) -> (Vec<model::ActivityEvent>, Option<String>) {
+        (self.events, self.cursor)
+    }
+}
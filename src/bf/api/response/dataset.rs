@@ -1,8 +1,10 @@
 // Copyright (c) 2018 Blackfynn, Inc. All Rights Reserved.
 
 use std::borrow::Borrow;
+use std::collections::HashMap;
 use std::ops::Deref;
 
+use chrono::{DateTime, NaiveDate, Utc};
 use serde_derive::Deserialize;
 
 use crate::bf::api::response::package::Package;
@@ -147,6 +149,32 @@ impl CollaboratorCounts {
     }
 }
 
+/// The outcome of changing the collaborators of a dataset, keyed by the
+/// ID of each user or team.
+#[derive(Debug, Clone, Eq, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CollaboratorChanges {
+    changes: HashMap<String, ChangeResponse>,
+    counts: CollaboratorCounts,
+}
+
+impl CollaboratorChanges {
+    /// Get the outcome of the change for every user or team.
+    pub fn changes(&self) -> &HashMap<String, ChangeResponse> {
+        &self.changes
+    }
+
+    /// Get the outcome of the change for a single user or team.
+    pub fn change<I: AsRef<str>>(&self, id: I) -> Option<&ChangeResponse> {
+        self.changes.get(id.as_ref())
+    }
+
+    /// Get the number of collaborators after the change.
+    pub fn counts(&self) -> &CollaboratorCounts {
+        &self.counts
+    }
+}
+
 #[derive(Debug, Clone, Eq, Hash, PartialEq, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ChangeResponse {
@@ -167,3 +195,92 @@ impl ChangeResponse {
         self.message.as_ref()
     }
 }
+
+/// The README of a dataset.
+#[derive(Debug, Clone, Eq, Hash, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Readme {
+    readme: String,
+}
+
+impl Readme {
+    pub fn take(self) -> String {
+        self.readme
+    }
+}
+
+/// The banner image of a dataset.
+#[derive(Debug, Clone, Eq, Hash, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Banner {
+    banner: Option<String>,
+}
+
+impl Banner {
+    /// A temporary URL the banner image can be downloaded from.
+    pub fn take(self) -> Option<String> {
+        self.banner
+    }
+}
+
+/// A DOI reserved for a dataset.
+#[derive(Debug, Clone, Eq, Hash, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Doi {
+    doi: String,
+    url: Option<String>,
+    state: Option<String>,
+}
+
+impl Doi {
+    /// The DOI itself (ex. 10.26275/abcd-efgh).
+    pub fn doi(&self) -> &String {
+        &self.doi
+    }
+
+    /// The URL the DOI resolves to, once the dataset is published.
+    pub fn url(&self) -> Option<&String> {
+        self.url.as_ref()
+    }
+
+    /// The state of the DOI with its registrar (ex. "draft", "findable").
+    pub fn state(&self) -> Option<&String> {
+        self.state.as_ref()
+    }
+}
+
+/// The publication state of a dataset.
+#[derive(Debug, Clone, Eq, Hash, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Publication {
+    status: model::PublicationStatus,
+    #[serde(rename = "type")]
+    publication_type: Option<model::PublicationType>,
+    embargo_release_date: Option<NaiveDate>,
+    comments: Option<String>,
+    updated_at: Option<DateTime<Utc>>,
+}
+
+impl Publication {
+    pub fn status(&self) -> &model::PublicationStatus {
+        &self.status
+    }
+
+    pub fn publication_type(&self) -> Option<model::PublicationType> {
+        self.publication_type
+    }
+
+    pub fn embargo_release_date(&self) -> Option<&NaiveDate> {
+        self.embargo_release_date.as_ref()
+    }
+
+    /// Comments left by the publishing team, such as the reason a request
+    /// was rejected.
+    pub fn comments(&self) -> Option<&String> {
+        self.comments.as_ref()
+    }
+
+    pub fn updated_at(&self) -> Option<&DateTime<Utc>> {
+        self.updated_at.as_ref()
+    }
+}
@@ -3,12 +3,14 @@
 //! Client response types to the Blackfynn API.
 
 mod account;
+mod activity;
 mod channel;
 mod dataset;
 mod file;
 mod mv;
 mod organization;
 mod package;
+mod search;
 mod security;
 mod team;
 mod upload;
@@ -20,13 +22,18 @@ use serde_derive::Deserialize;
 pub struct EmptyMap {}
 
 // Re-export
-pub use self::account::ApiSession;
+pub use self::account::{ApiKey, ApiSession};
+pub use self::activity::ActivityPage;
 pub use self::channel::Channel;
-pub use self::dataset::{ChangeResponse, CollaboratorCounts, Collaborators, Dataset};
+pub use self::dataset::{
+    Banner, ChangeResponse, CollaboratorChanges, CollaboratorCounts, Collaborators, Dataset, Doi,
+    Publication, Readme,
+};
 pub use self::file::{File, Files};
-pub use self::mv::MoveResponse;
-pub use self::organization::{Organization, OrganizationRole, Organizations};
+pub use self::mv::{DeleteFailure, DeleteResponse, MoveFailure, MoveResponse};
+pub use self::organization::{Invite, Organization, OrganizationRole, Organizations};
 pub use self::package::Package;
+pub use self::search::{Facet, FacetCount, SearchHit, SearchHitKind, SearchResults};
 pub use self::security::{TemporaryCredential, UploadCredential};
 pub use self::team::Team;
 pub use self::upload::{
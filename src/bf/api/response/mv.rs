@@ -1,5 +1,7 @@
 use serde_derive::Deserialize;
 
+/// The result of moving or deleting packages, listing the IDs of the
+/// packages changed along with the reason each remaining package was not.
 #[derive(Debug, Clone, PartialEq, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct MoveResponse {
@@ -8,7 +10,20 @@ pub struct MoveResponse {
     destination: Option<String>,
 }
 
+/// Deleting packages reports its result the same way moving them does.
+pub type DeleteResponse = MoveResponse;
+
+pub type DeleteFailure = MoveFailure;
+
 impl MoveResponse {
+    pub fn new(success: Vec<String>, failures: Vec<MoveFailure>) -> Self {
+        Self {
+            success,
+            failures,
+            destination: None,
+        }
+    }
+
     pub fn success(&self) -> &Vec<String> {
         self.success.as_ref()
     }
@@ -26,6 +41,17 @@ pub struct MoveFailure {
 }
 
 impl MoveFailure {
+    pub fn new<I, E>(id: I, error: E) -> Self
+    where
+        I: Into<String>,
+        E: Into<String>,
+    {
+        Self {
+            id: id.into(),
+            error: error.into(),
+        }
+    }
+
     pub fn id(&self) -> &String {
         &self.id
     }
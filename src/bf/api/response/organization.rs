@@ -3,6 +3,7 @@
 use std::slice;
 use std::vec;
 
+use chrono::{DateTime, Utc};
 use serde_derive::Deserialize;
 
 use crate::bf::model;
@@ -101,7 +102,7 @@ impl IntoIterator for Organizations {
 pub struct OrganizationRole {
     id: String,
     name: String,
-    role: Option<String>,
+    role: Option<model::Role>,
 }
 
 impl OrganizationRole {
@@ -113,7 +114,46 @@ impl OrganizationRole {
         &self.name
     }
 
-    pub fn role(&self) -> Option<&String> {
+    pub fn role(&self) -> Option<&model::Role> {
         self.role.as_ref()
     }
 }
+
+/// A pending invitation to join an organization.
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Invite {
+    id: String,
+    email: String,
+    first_name: String,
+    last_name: String,
+    role: Option<model::Role>,
+    valid_until: Option<DateTime<Utc>>,
+}
+
+impl Invite {
+    pub fn id(&self) -> &String {
+        &self.id
+    }
+
+    pub fn email(&self) -> &String {
+        &self.email
+    }
+
+    pub fn first_name(&self) -> &String {
+        &self.first_name
+    }
+
+    pub fn last_name(&self) -> &String {
+        &self.last_name
+    }
+
+    pub fn role(&self) -> Option<&model::Role> {
+        self.role.as_ref()
+    }
+
+    /// Get the time after which the invitation can no longer be accepted.
+    pub fn valid_until(&self) -> Option<&DateTime<Utc>> {
+        self.valid_until.as_ref()
+    }
+}
@@ -0,0 +1,213 @@
+// Copyright (c) 2018 Blackfynn, Inc. All Rights Reserved.
+
+use chrono::{DateTime, Utc};
+use serde_derive::Deserialize;
+
+use crate::bf::model::{DatasetNodeId, PackageId};
+
+/// Whether a search hit is a dataset or a package.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum SearchHitKind {
+    Dataset,
+    Package,
+}
+
+/// A dataset or package matching a search. Use
+/// `Blackfynn::get_search_hit_dataset` and
+/// `Blackfynn::get_search_hit_package` to fetch the full object.
+#[derive(Debug, Clone, Eq, Hash, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchHit {
+    kind: SearchHitKind,
+    id: String,
+    dataset_id: DatasetNodeId,
+    name: String,
+    package_type: Option<String>,
+    file_type: Option<String>,
+    created_at: DateTime<Utc>,
+    #[serde(default)]
+    highlights: Vec<String>,
+}
+
+impl SearchHit {
+    pub fn kind(&self) -> SearchHitKind {
+        self.kind
+    }
+
+    /// The node ID of the dataset or package.
+    pub fn id(&self) -> &String {
+        &self.id
+    }
+
+    /// The dataset the hit is, or belongs to.
+    pub fn dataset_id(&self) -> &DatasetNodeId {
+        &self.dataset_id
+    }
+
+    /// The ID of the package, if the hit is a package.
+    pub fn package_id(&self) -> Option<PackageId> {
+        match self.kind {
+            SearchHitKind::Package => Some(PackageId::new(self.id.as_str())),
+            SearchHitKind::Dataset => None,
+        }
+    }
+
+    pub fn name(&self) -> &String {
+        &self.name
+    }
+
+    pub fn package_type(&self) -> Option<&String> {
+        self.package_type.as_ref()
+    }
+
+    pub fn file_type(&self) -> Option<&String> {
+        self.file_type.as_ref()
+    }
+
+    pub fn created_at(&self) -> &DateTime<Utc> {
+        &self.created_at
+    }
+
+    /// Fragments of text matching the query.
+    pub fn highlights(&self) -> &Vec<String> {
+        &self.highlights
+    }
+}
+
+/// The number of hits sharing a facet value.
+#[derive(Debug, Clone, Eq, Hash, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FacetCount {
+    value: String,
+    count: u64,
+}
+
+impl FacetCount {
+    pub fn value(&self) -> &String {
+        &self.value
+    }
+
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+}
+
+/// The distribution of all hits over a facet, such as "dataset",
+/// "packageType", "fileType" or "createdAt".
+#[derive(Debug, Clone, Eq, Hash, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Facet {
+    name: String,
+    values: Vec<FacetCount>,
+}
+
+impl Facet {
+    pub fn name(&self) -> &String {
+        &self.name
+    }
+
+    pub fn values(&self) -> &Vec<FacetCount> {
+        &self.values
+    }
+}
+
+/// A page of search hits.
+#[derive(Debug, Clone, Eq, Hash, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchResults {
+    hits: Vec<SearchHit>,
+    total_count: u64,
+    #[serde(default)]
+    facets: Vec<Facet>,
+    limit: u32,
+    offset: u32,
+}
+
+impl SearchResults {
+    pub fn hits(&self) -> &Vec<SearchHit> {
+        &self.hits
+    }
+
+    /// The number of hits across every page.
+    pub fn total_count(&self) -> u64 {
+        self.total_count
+    }
+
+    pub fn facets(&self) -> &Vec<Facet> {
+        &self.facets
+    }
+
+    /// Get a facet by name.
+    pub fn facet(&self, name: &str) -> Option<&Facet> {
+        self.facets.iter().find(|f| f.name() == name)
+    }
+
+    pub fn limit(&self) -> u32 {
+        self.limit
+    }
+
+    pub fn offset(&self) -> u32 {
+        self.offset
+    }
+
+    /// Tests if there are hits after this page.
+    pub fn has_more(&self) -> bool {
+        !self.hits.is_empty()
+            && u64::from(self.offset) + (self.hits.len() as u64) < self.total_count
+    }
+
+    /// The offset of the next page, if there are hits after this page. The
+    /// server may return fewer hits than were asked for, so the offset
+    /// follows the hits actually returned.
+    pub fn next_offset(&self) -> Option<u32> {
+        if self.has_more() {
+            Some(self.offset + self.hits.len() as u32)
+        } else {
+            None
+        }
+    }
+
+    pub fn take(self) -> Vec<SearchHit> {
+        self.hits
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn page(offset: u32, hits: usize, total_count: u64) -> SearchResults {
+        let hit = serde_json::json!({
+            "kind": "package",
+            "id": "N:package:1",
+            "datasetId": "N:dataset:1",
+            "name": "EEG",
+            "packageType": "TimeSeries",
+            "fileType": "EDF",
+            "createdAt": "2018-01-01T00:00:00Z",
+        });
+        serde_json::from_value(serde_json::json!({
+            "hits": vec![hit; hits],
+            "totalCount": total_count,
+            "limit": 10,
+            "offset": offset,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn pages_follow_the_hits_returned() {
+        // 50 hits were asked for, but the server capped the page at 10:
+        let first = page(0, 10, 25);
+        assert!(first.has_more());
+        assert_eq!(first.next_offset(), Some(10));
+
+        let second = page(10, 10, 25);
+        assert_eq!(second.next_offset(), Some(20));
+
+        let last = page(20, 5, 25);
+        assert!(!last.has_more());
+        assert_eq!(last.next_offset(), None);
+    }
+}
@@ -8,6 +8,8 @@ use failure::{Backtrace, Context, Fail};
 
 use hyper::http::header::ToStrError;
 
+use crate::bf::model::{PublicationBlockers, PublicationRule, RecordId};
+
 /// Type alias for handling errors throughout the agent
 pub type Result<T> = result::Result<T, Error>;
 
@@ -38,10 +40,73 @@ impl Error {
         .into()
     }
 
+    pub fn checksum_mismatch<S, E, A>(file_name: S, expected: E, actual: A) -> Error
+    where
+        S: Into<String>,
+        E: Into<String>,
+        A: Into<String>,
+    {
+        ErrorKind::ChecksumMismatch {
+            file_name: file_name.into(),
+            expected: expected.into(),
+            actual: actual.into(),
+        }
+        .into()
+    }
+
+    pub fn cancelled() -> Error {
+        ErrorKind::Cancelled.into()
+    }
+
     pub fn invalid_dataset_name<S: Into<String>>(name: S) -> Error {
         ErrorKind::InvalidDatasetName { name: name.into() }.into()
     }
 
+    pub fn invalid_package_path<S: Into<String>>(path: S) -> Error {
+        ErrorKind::InvalidPackagePath { path: path.into() }.into()
+    }
+
+    pub fn forbidden<S: Into<String>>(message: S) -> Error {
+        ErrorKind::Forbidden {
+            message: message.into(),
+        }
+        .into()
+    }
+
+    pub fn conflict<S: Into<String>>(message: S) -> Error {
+        ErrorKind::Conflict {
+            message: message.into(),
+        }
+        .into()
+    }
+
+    pub fn publication_blocked(rules: Vec<PublicationRule>) -> Error {
+        ErrorKind::PublicationBlocked {
+            rules: PublicationBlockers::new(rules),
+        }
+        .into()
+    }
+
+    /// An annotation import that failed after `created` annotations, the
+    /// first rows of the import, were created.
+    pub fn annotation_import_failed(created: usize, error: &Error) -> Error {
+        ErrorKind::AnnotationImportFailed {
+            created,
+            error: Box::new(error.kind().clone()),
+        }
+        .into()
+    }
+
+    /// An update of several records that failed after the records
+    /// `updated` were updated.
+    pub fn record_update_failed(updated: Vec<RecordId>, error: &Error) -> Error {
+        ErrorKind::RecordUpdateFailed {
+            updated,
+            error: Box::new(error.kind().clone()),
+        }
+        .into()
+    }
+
     pub fn invalid_arguments<S: Into<String>>(message: S) -> Error {
         ErrorKind::InvalidArguments {
             message: message.into(),
@@ -114,9 +179,25 @@ pub enum ErrorKind {
     #[fail(display = "couldn't find dataset: \"{}\"", name)]
     InvalidDatasetName { name: String },
 
+    #[fail(display = "couldn't find package: \"{}\"", path)]
+    InvalidPackagePath { path: String },
+
     #[fail(display = "upload error: {}", message)]
     UploadError { message: String },
 
+    #[fail(
+        display = "checksum mismatch for \"{}\": expected {}, got {}",
+        file_name, expected, actual
+    )]
+    ChecksumMismatch {
+        file_name: String,
+        expected: String,
+        actual: String,
+    },
+
+    #[fail(display = "operation cancelled")]
+    Cancelled,
+
     #[fail(display = "invalid environment string: {}", value)]
     EnvParseError { value: String },
 
@@ -141,15 +222,51 @@ pub enum ErrorKind {
     #[fail(display = "path is not a directory: {:?}", path)]
     PathIsNotADirectory { path: PathBuf },
 
+    #[fail(display = "dataset cannot be published: {}", rules)]
+    PublicationBlocked { rules: PublicationBlockers },
+
+    #[fail(
+        display = "annotation import failed after {} annotations were created: {}",
+        created, error
+    )]
+    AnnotationImportFailed {
+        created: usize,
+        error: Box<ErrorKind>,
+    },
+
+    #[fail(
+        display = "record update failed after updating records {:?}: {}",
+        updated, error
+    )]
+    RecordUpdateFailed {
+        updated: Vec<RecordId>,
+        error: Box<ErrorKind>,
+    },
+
+    #[fail(display = "forbidden: {}", message)]
+    Forbidden { message: String },
+
+    #[fail(
+        display = "conflict: the resource was changed since it was last fetched: {}",
+        message
+    )]
+    Conflict { message: String },
+
     #[fail(display = "no organization set")]
     NoOrganizationSet,
 
     #[fail(display = "missing upload id")]
     S3MissingUploadId,
 
+    #[fail(display = "the created API key has no secret")]
+    MissingApiKeySecret,
+
     #[fail(display = "io error: {}", error)]
     IoError { error: String },
 
+    #[fail(display = "directory walk error: {}", error)]
+    WalkError { error: String },
+
     #[fail(display = "strip prefix error: {}", error)]
     StripPrefixError { error: String },
 
@@ -162,6 +279,9 @@ pub enum ErrorKind {
     #[fail(display = "json serialization error: {}", error)]
     SerdeJsonError { error: String },
 
+    #[fail(display = "csv error: {}", error)]
+    CsvError { error: String },
+
     #[fail(display = "error parsing string: {}", error)]
     ParseIntError { error: String },
 }
@@ -186,6 +306,15 @@ impl From<std::path::StripPrefixError> for Error {
     }
 }
 
+/// map from directory walking errors
+impl From<ignore::Error> for Error {
+    fn from(error: ignore::Error) -> Error {
+        Error::from(Context::new(ErrorKind::WalkError {
+            error: error.to_string(),
+        }))
+    }
+}
+
 /// map from io errors
 impl From<io::Error> for Error {
     fn from(error: io::Error) -> Error {
@@ -195,6 +324,15 @@ impl From<io::Error> for Error {
     }
 }
 
+/// map from csv errors
+impl From<csv::Error> for Error {
+    fn from(error: csv::Error) -> Error {
+        Error::from(Context::new(ErrorKind::CsvError {
+            error: error.to_string(),
+        }))
+    }
+}
+
 /// map from serde_json errors
 impl From<serde_json::Error> for Error {
     fn from(error: serde_json::Error) -> Error {
@@ -0,0 +1,223 @@
+// Copyright (c) 2018 Blackfynn, Inc. All Rights Reserved.
+
+use std::convert::TryFrom;
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+use serde_derive::{Deserialize, Serialize};
+
+use crate::bf::model::{PackageId, Role, UserId};
+
+/// The kinds of events recorded in the activity history of a dataset.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ActivityEventType {
+    Upload,
+    Move,
+    Rename,
+    Delete,
+    PermissionChange,
+    Other,
+}
+
+impl ActivityEventType {
+    pub fn as_str(&self) -> &str {
+        match self {
+            ActivityEventType::Upload => "UPLOAD",
+            ActivityEventType::Move => "MOVE",
+            ActivityEventType::Rename => "RENAME",
+            ActivityEventType::Delete => "DELETE",
+            ActivityEventType::PermissionChange => "PERMISSION_CHANGE",
+            ActivityEventType::Other => "OTHER",
+        }
+    }
+}
+
+impl fmt::Display for ActivityEventType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// What happened in an `ActivityEvent`.
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(
+    tag = "eventType",
+    content = "detail",
+    rename_all = "SCREAMING_SNAKE_CASE"
+)]
+pub enum ActivityDetail {
+    /// A package was uploaded.
+    #[serde(rename_all = "camelCase")]
+    Upload { package_id: PackageId, name: String },
+    /// A package was moved, for example with `Blackfynn::mv`. A missing
+    /// parent is the root of the dataset.
+    #[serde(rename_all = "camelCase")]
+    Move {
+        package_id: PackageId,
+        from_parent: Option<PackageId>,
+        to_parent: Option<PackageId>,
+    },
+    /// A package was renamed.
+    #[serde(rename_all = "camelCase")]
+    Rename {
+        package_id: PackageId,
+        old_name: String,
+        new_name: String,
+    },
+    /// A package was deleted.
+    #[serde(rename_all = "camelCase")]
+    Delete { package_id: PackageId, name: String },
+    /// The role of a user, team or organization on the dataset changed. A
+    /// missing role means access was removed.
+    #[serde(rename_all = "camelCase")]
+    PermissionChange {
+        collaborator_id: String,
+        role: Option<Role>,
+    },
+    /// An event not known to this client, with its event type as sent by
+    /// the platform.
+    Other(String),
+}
+
+impl ActivityDetail {
+    pub fn event_type(&self) -> ActivityEventType {
+        match self {
+            ActivityDetail::Upload { .. } => ActivityEventType::Upload,
+            ActivityDetail::Move { .. } => ActivityEventType::Move,
+            ActivityDetail::Rename { .. } => ActivityEventType::Rename,
+            ActivityDetail::Delete { .. } => ActivityEventType::Delete,
+            ActivityDetail::PermissionChange { .. } => ActivityEventType::PermissionChange,
+            ActivityDetail::Other(_) => ActivityEventType::Other,
+        }
+    }
+
+    /// The package the event is about, if any.
+    pub fn package_id(&self) -> Option<&PackageId> {
+        match self {
+            ActivityDetail::Upload { package_id, .. }
+            | ActivityDetail::Move { package_id, .. }
+            | ActivityDetail::Rename { package_id, .. }
+            | ActivityDetail::Delete { package_id, .. } => Some(package_id),
+            _ => None,
+        }
+    }
+}
+
+// An `ActivityEvent` as sent by the platform, before its detail is typed.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawActivityEvent {
+    id: String,
+    user_id: UserId,
+    timestamp: DateTime<Utc>,
+    event_type: String,
+    #[serde(default)]
+    detail: serde_json::Value,
+}
+
+impl TryFrom<RawActivityEvent> for ActivityEvent {
+    type Error = serde_json::Error;
+
+    fn try_from(raw: RawActivityEvent) -> Result<Self, Self::Error> {
+        let known = [
+            ActivityEventType::Upload,
+            ActivityEventType::Move,
+            ActivityEventType::Rename,
+            ActivityEventType::Delete,
+            ActivityEventType::PermissionChange,
+        ];
+
+        // Events not known to this client are kept with their type, but
+        // without their detail:
+        let detail = if known.iter().any(|t| t.as_str() == raw.event_type) {
+            serde_json::from_value(serde_json::json!({
+                "eventType": raw.event_type,
+                "detail": raw.detail,
+            }))?
+        } else {
+            ActivityDetail::Other(raw.event_type)
+        };
+
+        Ok(Self {
+            id: raw.id,
+            user_id: raw.user_id,
+            timestamp: raw.timestamp,
+            detail,
+        })
+    }
+}
+
+/// An entry in the activity history of a dataset.
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase", try_from = "RawActivityEvent")]
+pub struct ActivityEvent {
+    id: String,
+    user_id: UserId,
+    timestamp: DateTime<Utc>,
+    #[serde(flatten)]
+    detail: ActivityDetail,
+}
+
+impl ActivityEvent {
+    pub fn id(&self) -> &String {
+        &self.id
+    }
+
+    /// The user who caused the event.
+    pub fn user_id(&self) -> &UserId {
+        &self.user_id
+    }
+
+    pub fn timestamp(&self) -> &DateTime<Utc> {
+        &self.timestamp
+    }
+
+    pub fn detail(&self) -> &ActivityDetail {
+        &self.detail
+    }
+
+    pub fn event_type(&self) -> ActivityEventType {
+        self.detail.event_type()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn events_are_typed_by_their_detail() {
+        let events: Vec<ActivityEvent> = serde_json::from_value(serde_json::json!([
+            {
+                "id": "1",
+                "userId": "N:user:1",
+                "timestamp": "2019-04-01T12:00:00Z",
+                "eventType": "MOVE",
+                "detail": {
+                    "packageId": "N:package:1",
+                    "fromParent": null,
+                    "toParent": "N:collection:1"
+                }
+            },
+            {
+                "id": "2",
+                "userId": "N:user:1",
+                "timestamp": "2019-04-01T12:05:00Z",
+                "eventType": "ARCHIVE",
+                "detail": {}
+            }
+        ]))
+        .unwrap();
+
+        assert_eq!(events[0].event_type(), ActivityEventType::Move);
+        assert_eq!(
+            events[0].detail().package_id(),
+            Some(&PackageId::new("N:package:1"))
+        );
+        assert_eq!(
+            events[1].detail(),
+            &ActivityDetail::Other("ARCHIVE".to_string())
+        );
+    }
+}
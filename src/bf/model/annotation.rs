@@ -0,0 +1,198 @@
+// Copyright (c) 2018 Blackfynn, Inc. All Rights Reserved.
+use std::fmt;
+use std::ops::Deref;
+
+use serde_derive::{Deserialize, Serialize};
+
+use crate::bf::api::{BFId, BFName};
+use crate::bf::model::ChannelId;
+
+/// An integer identifier for an annotation layer of a timeseries package.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct AnnotationLayerId(u32);
+
+impl AnnotationLayerId {
+    #[allow(dead_code)]
+    pub fn new(id: u32) -> Self {
+        AnnotationLayerId(id)
+    }
+
+    /// Unwraps the value.
+    #[allow(dead_code)]
+    pub fn take(self) -> u32 {
+        self.0
+    }
+}
+
+impl Deref for AnnotationLayerId {
+    type Target = u32;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl From<AnnotationLayerId> for u32 {
+    fn from(id: AnnotationLayerId) -> Self {
+        id.0
+    }
+}
+
+impl From<u32> for AnnotationLayerId {
+    fn from(id: u32) -> Self {
+        Self::new(id)
+    }
+}
+
+impl From<AnnotationLayerId> for String {
+    fn from(id: AnnotationLayerId) -> Self {
+        id.0.to_string()
+    }
+}
+
+impl fmt::Display for AnnotationLayerId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// An integer identifier for an annotation within a layer.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct AnnotationId(u32);
+
+impl AnnotationId {
+    #[allow(dead_code)]
+    pub fn new(id: u32) -> Self {
+        AnnotationId(id)
+    }
+
+    /// Unwraps the value.
+    #[allow(dead_code)]
+    pub fn take(self) -> u32 {
+        self.0
+    }
+}
+
+impl Deref for AnnotationId {
+    type Target = u32;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl From<AnnotationId> for u32 {
+    fn from(id: AnnotationId) -> Self {
+        id.0
+    }
+}
+
+impl From<u32> for AnnotationId {
+    fn from(id: u32) -> Self {
+        Self::new(id)
+    }
+}
+
+impl From<AnnotationId> for String {
+    fn from(id: AnnotationId) -> Self {
+        id.0.to_string()
+    }
+}
+
+impl fmt::Display for AnnotationId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// A named layer of annotations, such as seizure markers or sleep stages,
+/// attached to a timeseries package.
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnnotationLayer {
+    id: AnnotationLayerId,
+    name: String,
+    description: Option<String>,
+    color: Option<String>,
+}
+
+impl BFId for AnnotationLayer {
+    type Id = AnnotationLayerId;
+    fn id(&self) -> &Self::Id {
+        self.id()
+    }
+}
+
+impl BFName for AnnotationLayer {
+    fn name(&self) -> &String {
+        self.name()
+    }
+}
+
+impl AnnotationLayer {
+    pub fn id(&self) -> &AnnotationLayerId {
+        &self.id
+    }
+
+    pub fn name(&self) -> &String {
+        &self.name
+    }
+
+    pub fn description(&self) -> Option<&String> {
+        self.description.as_ref()
+    }
+
+    pub fn color(&self) -> Option<&String> {
+        self.color.as_ref()
+    }
+}
+
+/// A labelled time range spanning one or more channels of a timeseries
+/// package. `start` and `end` are in microseconds, like
+/// `Channel::start()` and `Channel::end()`.
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Annotation {
+    id: AnnotationId,
+    layer_id: AnnotationLayerId,
+    channel_ids: Vec<ChannelId>,
+    start: i64,
+    end: i64,
+    label: String,
+    description: Option<String>,
+}
+
+impl BFId for Annotation {
+    type Id = AnnotationId;
+    fn id(&self) -> &Self::Id {
+        self.id()
+    }
+}
+
+impl Annotation {
+    pub fn id(&self) -> &AnnotationId {
+        &self.id
+    }
+
+    pub fn layer_id(&self) -> &AnnotationLayerId {
+        &self.layer_id
+    }
+
+    pub fn channel_ids(&self) -> &Vec<ChannelId> {
+        &self.channel_ids
+    }
+
+    pub fn start(&self) -> i64 {
+        self.start
+    }
+
+    pub fn end(&self) -> i64 {
+        self.end
+    }
+
+    pub fn label(&self) -> &String {
+        &self.label
+    }
+
+    pub fn description(&self) -> Option<&String> {
+        self.description.as_ref()
+    }
+}
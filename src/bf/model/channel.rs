@@ -1,12 +1,82 @@
 // Copyright (c) 2018 Blackfynn, Inc. All Rights Reserved.
+use std::borrow::Borrow;
+use std::fmt;
+use std::ops::Deref;
+
 use serde_derive::{Deserialize, Serialize};
 
-use crate::bf::api::BFName;
+use crate::bf::api::{BFId, BFName};
+
+/// An identifier for a timeseries channel on the Blackfynn platform.
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct ChannelId(String);
+
+impl ChannelId {
+    #[allow(dead_code)]
+    pub fn new<S: Into<String>>(id: S) -> Self {
+        ChannelId(id.into())
+    }
+
+    /// Unwraps the value.
+    pub fn take(self) -> String {
+        self.0
+    }
+}
+
+impl Borrow<String> for ChannelId {
+    fn borrow(&self) -> &String {
+        &self.0
+    }
+}
+
+impl Borrow<str> for ChannelId {
+    fn borrow(&self) -> &str {
+        self.0.as_str()
+    }
+}
+
+impl Deref for ChannelId {
+    type Target = String;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl From<ChannelId> for String {
+    fn from(id: ChannelId) -> String {
+        id.0
+    }
+}
+
+impl<'a> From<&'a ChannelId> for String {
+    fn from(id: &'a ChannelId) -> String {
+        id.0.to_string()
+    }
+}
+
+impl From<String> for ChannelId {
+    fn from(id: String) -> Self {
+        Self::new(id)
+    }
+}
+
+impl<'a> From<&'a str> for ChannelId {
+    fn from(id: &'a str) -> Self {
+        Self::new(String::from(id))
+    }
+}
+
+impl fmt::Display for ChannelId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
 
 /// A Blackfynn timeseries channel.
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Channel {
+    id: ChannelId,
     name: String,
     rate: f64,
     start: i64,
@@ -17,6 +87,13 @@ pub struct Channel {
     group: Option<String>,
 }
 
+impl BFId for Channel {
+    type Id = ChannelId;
+    fn id(&self) -> &Self::Id {
+        self.id()
+    }
+}
+
 impl BFName for Channel {
     fn name(&self) -> &String {
         &self.name
@@ -24,6 +101,10 @@ impl BFName for Channel {
 }
 
 impl Channel {
+    pub fn id(&self) -> &ChannelId {
+        &self.id
+    }
+
     pub fn rate(&self) -> f64 {
         self.rate
     }
@@ -48,3 +129,98 @@ impl Channel {
         self.group.as_ref()
     }
 }
+
+/// A block of consecutive samples of a timeseries channel. Timestamps are
+/// in microseconds, like `Channel::start()` and `Channel::end()`.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SampleBlock {
+    channel_id: ChannelId,
+    timestamps: Vec<i64>,
+    values: Vec<f64>,
+}
+
+impl SampleBlock {
+    pub fn new(channel_id: ChannelId, timestamps: Vec<i64>, values: Vec<f64>) -> Self {
+        Self {
+            channel_id,
+            timestamps,
+            values,
+        }
+    }
+
+    pub fn channel_id(&self) -> &ChannelId {
+        &self.channel_id
+    }
+
+    pub fn timestamps(&self) -> &Vec<i64> {
+        &self.timestamps
+    }
+
+    pub fn values(&self) -> &Vec<f64> {
+        &self.values
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Iterate over the samples of the block as `(timestamp, value)` pairs.
+    pub fn samples(&self) -> impl Iterator<Item = (i64, f64)> + '_ {
+        self.timestamps
+            .iter()
+            .cloned()
+            .zip(self.values.iter().cloned())
+    }
+}
+
+/// A `SampleBlock` numbered by the order it was written in by a
+/// streaming writer, letting the platform detect duplicate and missing
+/// blocks.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StreamBlock {
+    sequence: u64,
+    #[serde(flatten)]
+    samples: SampleBlock,
+}
+
+impl StreamBlock {
+    pub fn new(sequence: u64, samples: SampleBlock) -> Self {
+        Self { sequence, samples }
+    }
+
+    pub fn sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    pub fn samples(&self) -> &SampleBlock {
+        &self.samples
+    }
+
+    pub fn take(self) -> SampleBlock {
+        self.samples
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sample_blocks_pair_timestamps_with_values() {
+        let block: SampleBlock = serde_json::from_str(
+            r#"{"channelId": "N:channel:1", "timestamps": [0, 1000], "values": [0.5, -1.5]}"#,
+        )
+        .unwrap();
+        assert_eq!(block.channel_id(), &ChannelId::new("N:channel:1"));
+        assert_eq!(
+            block.samples().collect::<Vec<_>>(),
+            vec![(0, 0.5), (1000, -1.5)]
+        );
+    }
+}
@@ -0,0 +1,508 @@
+// Copyright (c) 2018 Blackfynn, Inc. All Rights Reserved.
+use std::borrow::Borrow;
+use std::fmt;
+use std::ops::Deref;
+
+use serde_derive::{Deserialize, Serialize};
+
+use crate::bf::api::{BFId, BFName};
+use crate::bf::model::{PropertyType, PropertyValue};
+use crate::bf::{Error, Result};
+
+/// An identifier for a metadata concept (model) of a dataset.
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct ConceptId(String);
+
+impl ConceptId {
+    #[allow(dead_code)]
+    pub fn new<S: Into<String>>(id: S) -> Self {
+        ConceptId(id.into())
+    }
+
+    /// Unwraps the value.
+    pub fn take(self) -> String {
+        self.0
+    }
+}
+
+impl Borrow<String> for ConceptId {
+    fn borrow(&self) -> &String {
+        &self.0
+    }
+}
+
+impl Borrow<str> for ConceptId {
+    fn borrow(&self) -> &str {
+        self.0.as_str()
+    }
+}
+
+impl Deref for ConceptId {
+    type Target = String;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl From<ConceptId> for String {
+    fn from(id: ConceptId) -> String {
+        id.0
+    }
+}
+
+impl<'a> From<&'a ConceptId> for String {
+    fn from(id: &'a ConceptId) -> String {
+        id.0.to_string()
+    }
+}
+
+impl From<String> for ConceptId {
+    fn from(id: String) -> Self {
+        Self::new(id)
+    }
+}
+
+impl<'a> From<&'a str> for ConceptId {
+    fn from(id: &'a str) -> Self {
+        Self::new(String::from(id))
+    }
+}
+
+impl fmt::Display for ConceptId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// An identifier for a metadata record.
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct RecordId(String);
+
+impl RecordId {
+    #[allow(dead_code)]
+    pub fn new<S: Into<String>>(id: S) -> Self {
+        RecordId(id.into())
+    }
+
+    /// Unwraps the value.
+    pub fn take(self) -> String {
+        self.0
+    }
+}
+
+impl Borrow<String> for RecordId {
+    fn borrow(&self) -> &String {
+        &self.0
+    }
+}
+
+impl Borrow<str> for RecordId {
+    fn borrow(&self) -> &str {
+        self.0.as_str()
+    }
+}
+
+impl Deref for RecordId {
+    type Target = String;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl From<RecordId> for String {
+    fn from(id: RecordId) -> String {
+        id.0
+    }
+}
+
+impl<'a> From<&'a RecordId> for String {
+    fn from(id: &'a RecordId) -> String {
+        id.0.to_string()
+    }
+}
+
+impl From<String> for RecordId {
+    fn from(id: String) -> Self {
+        Self::new(id)
+    }
+}
+
+impl<'a> From<&'a str> for RecordId {
+    fn from(id: &'a str) -> Self {
+        Self::new(String::from(id))
+    }
+}
+
+impl fmt::Display for RecordId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// An identifier for a type of relationship between metadata records.
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct RelationshipId(String);
+
+impl RelationshipId {
+    #[allow(dead_code)]
+    pub fn new<S: Into<String>>(id: S) -> Self {
+        RelationshipId(id.into())
+    }
+
+    /// Unwraps the value.
+    pub fn take(self) -> String {
+        self.0
+    }
+}
+
+impl Borrow<String> for RelationshipId {
+    fn borrow(&self) -> &String {
+        &self.0
+    }
+}
+
+impl Borrow<str> for RelationshipId {
+    fn borrow(&self) -> &str {
+        self.0.as_str()
+    }
+}
+
+impl Deref for RelationshipId {
+    type Target = String;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl From<RelationshipId> for String {
+    fn from(id: RelationshipId) -> String {
+        id.0
+    }
+}
+
+impl<'a> From<&'a RelationshipId> for String {
+    fn from(id: &'a RelationshipId) -> String {
+        id.0.to_string()
+    }
+}
+
+impl From<String> for RelationshipId {
+    fn from(id: String) -> Self {
+        Self::new(id)
+    }
+}
+
+impl<'a> From<&'a str> for RelationshipId {
+    fn from(id: &'a str) -> Self {
+        Self::new(String::from(id))
+    }
+}
+
+impl fmt::Display for RelationshipId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// A metadata concept, or model, of a dataset: a named schema records are
+/// created against, like "subject" or "sample".
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Concept {
+    id: ConceptId,
+    name: String,
+    display_name: String,
+    description: Option<String>,
+    count: Option<u64>,
+}
+
+impl BFId for Concept {
+    type Id = ConceptId;
+    fn id(&self) -> &Self::Id {
+        self.id()
+    }
+}
+
+impl BFName for Concept {
+    fn name(&self) -> &String {
+        self.name()
+    }
+}
+
+impl Concept {
+    pub fn id(&self) -> &ConceptId {
+        &self.id
+    }
+
+    pub fn name(&self) -> &String {
+        &self.name
+    }
+
+    pub fn display_name(&self) -> &String {
+        &self.display_name
+    }
+
+    pub fn description(&self) -> Option<&String> {
+        self.description.as_ref()
+    }
+
+    /// The number of records of the concept.
+    pub fn count(&self) -> Option<u64> {
+        self.count
+    }
+}
+
+/// A typed property in the schema of a concept.
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConceptProperty {
+    name: String,
+    display_name: String,
+    data_type: PropertyType,
+    #[serde(default)]
+    concept_title: bool,
+    #[serde(default)]
+    required: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+}
+
+impl ConceptProperty {
+    pub fn new<N: Into<String>>(name: N, data_type: PropertyType) -> Self {
+        let name = name.into();
+        Self {
+            display_name: name.clone(),
+            name,
+            data_type,
+            concept_title: false,
+            required: false,
+            description: None,
+        }
+    }
+
+    pub fn with_display_name<D: Into<String>>(mut self, display_name: D) -> Self {
+        self.display_name = display_name.into();
+        self
+    }
+
+    /// Use the property as the title of records. A concept has exactly one
+    /// title property, which is always required.
+    pub fn with_title(mut self, concept_title: bool) -> Self {
+        self.concept_title = concept_title;
+        self.required = self.required || concept_title;
+        self
+    }
+
+    pub fn with_required(mut self, required: bool) -> Self {
+        self.required = required;
+        self
+    }
+
+    pub fn with_description<D: Into<String>>(mut self, description: D) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    pub fn name(&self) -> &String {
+        &self.name
+    }
+
+    pub fn display_name(&self) -> &String {
+        &self.display_name
+    }
+
+    pub fn data_type(&self) -> PropertyType {
+        self.data_type
+    }
+
+    pub fn is_title(&self) -> bool {
+        self.concept_title
+    }
+
+    pub fn is_required(&self) -> bool {
+        self.required
+    }
+
+    pub fn description(&self) -> Option<&String> {
+        self.description.as_ref()
+    }
+}
+
+/// The value of a single property of a record.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordValue {
+    name: String,
+    value: PropertyValue,
+}
+
+impl RecordValue {
+    pub fn new<N, V>(name: N, value: V) -> Self
+    where
+        N: Into<String>,
+        V: Into<PropertyValue>,
+    {
+        Self {
+            name: name.into(),
+            value: value.into(),
+        }
+    }
+
+    pub fn name(&self) -> &String {
+        &self.name
+    }
+
+    pub fn value(&self) -> &PropertyValue {
+        &self.value
+    }
+}
+
+/// A metadata record: an instance of a concept.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Record {
+    id: RecordId,
+    values: Vec<RecordValue>,
+}
+
+impl BFId for Record {
+    type Id = RecordId;
+    fn id(&self) -> &Self::Id {
+        self.id()
+    }
+}
+
+impl Record {
+    pub fn id(&self) -> &RecordId {
+        &self.id
+    }
+
+    pub fn values(&self) -> &Vec<RecordValue> {
+        &self.values
+    }
+
+    /// Get the value of a property of the record.
+    pub fn get(&self, name: &str) -> Option<&PropertyValue> {
+        self.values
+            .iter()
+            .find(|v| v.name() == name)
+            .map(RecordValue::value)
+    }
+
+    /// Type the values of a decoded record by the schema of its concept.
+    /// Values of properties missing from the schema are left as decoded.
+    pub fn with_schema(mut self, schema: &[ConceptProperty]) -> Result<Self> {
+        for record_value in &mut self.values {
+            if let Some(property) = schema.iter().find(|p| p.name() == &record_value.name) {
+                let value = record_value.value.clone();
+                record_value.value = value.with_type(property.data_type()).map_err(|err| {
+                    Error::invalid_arguments(format!("property {}: {}", property.name(), err))
+                })?;
+            }
+        }
+        Ok(self)
+    }
+}
+
+/// A type of relationship between the records of a dataset, such as
+/// "sample of" between samples and subjects.
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RelationshipType {
+    id: RelationshipId,
+    name: String,
+    display_name: String,
+    description: Option<String>,
+}
+
+impl BFId for RelationshipType {
+    type Id = RelationshipId;
+    fn id(&self) -> &Self::Id {
+        self.id()
+    }
+}
+
+impl BFName for RelationshipType {
+    fn name(&self) -> &String {
+        self.name()
+    }
+}
+
+impl RelationshipType {
+    pub fn id(&self) -> &RelationshipId {
+        &self.id
+    }
+
+    pub fn name(&self) -> &String {
+        &self.name
+    }
+
+    pub fn display_name(&self) -> &String {
+        &self.display_name
+    }
+
+    pub fn description(&self) -> Option<&String> {
+        self.description.as_ref()
+    }
+}
+
+/// A relationship from one record to another.
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordRelationship {
+    id: String,
+    from: RecordId,
+    to: RecordId,
+}
+
+impl RecordRelationship {
+    pub fn id(&self) -> &String {
+        &self.id
+    }
+
+    pub fn from(&self) -> &RecordId {
+        &self.from
+    }
+
+    pub fn to(&self) -> &RecordId {
+        &self.to
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_are_typed_by_their_schema() {
+        let record: Record = serde_json::from_str(
+            r#"{
+                "id": "N:record:1",
+                "values": [
+                    {"name": "name", "value": "2019-04-01T12:00:00Z"},
+                    {"name": "enrolled", "value": "2019-04-01T12:00:00Z"},
+                    {"name": "weight", "value": 62}
+                ]
+            }"#,
+        )
+        .unwrap();
+        let schema = vec![
+            ConceptProperty::new("name", PropertyType::String),
+            ConceptProperty::new("enrolled", PropertyType::Date),
+            ConceptProperty::new("weight", PropertyType::Double),
+        ];
+
+        let record = record.with_schema(&schema).unwrap();
+        assert_eq!(
+            record.get("name"),
+            Some(&PropertyValue::String("2019-04-01T12:00:00Z".to_string()))
+        );
+        assert_eq!(
+            record.get("enrolled"),
+            Some(&PropertyValue::Date(
+                "2019-04-01T12:00:00Z".parse().unwrap()
+            ))
+        );
+        assert_eq!(record.get("weight"), Some(&PropertyValue::Double(62.0)));
+    }
+}
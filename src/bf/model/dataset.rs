@@ -8,6 +8,8 @@ use chrono::{DateTime, Utc};
 use serde_derive::{Deserialize, Serialize};
 
 use crate::bf::api::{BFId, BFName};
+use crate::bf::model::DatasetStatus;
+use crate::bf::{Error, Result};
 
 /// An node identifier for a Blackfynn dataset (ex. N:dataset:c905919f-56f5-43ae-9c2a-8d5d542c133b).
 #[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
@@ -122,6 +124,97 @@ impl fmt::Display for DatasetId {
     }
 }
 
+/// Tests if a string is a well formed ORCID iD (ex. 0000-0002-1825-0097),
+/// including its check digit.
+pub fn is_valid_orcid(orcid: &str) -> bool {
+    // Group lengths are counted in bytes, which only count characters in
+    // ASCII strings:
+    if !orcid.is_ascii() {
+        return false;
+    }
+
+    let groups: Vec<&str> = orcid.split('-').collect();
+    if groups.len() != 4 || groups.iter().any(|g| g.len() != 4) {
+        return false;
+    }
+
+    let chars: Vec<char> = groups.concat().chars().collect();
+    let (digits, check) = chars.split_at(15);
+    if !digits.iter().all(|c| c.is_ascii_digit()) {
+        return false;
+    }
+
+    let total = digits
+        .iter()
+        .fold(0, |total, c| (total + c.to_digit(10).unwrap()) * 2);
+    let expected = match (12 - total % 11) % 11 {
+        10 => 'X',
+        n => std::char::from_digit(n, 10).unwrap(),
+    };
+    check[0] == expected
+}
+
+/// A person credited for their work on a dataset.
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Contributor {
+    first_name: String,
+    last_name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    email: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    orcid: Option<String>,
+}
+
+impl Contributor {
+    pub fn new<F, L>(first_name: F, last_name: L) -> Self
+    where
+        F: Into<String>,
+        L: Into<String>,
+    {
+        Self {
+            first_name: first_name.into(),
+            last_name: last_name.into(),
+            email: None,
+            orcid: None,
+        }
+    }
+
+    pub fn with_email<E: Into<String>>(mut self, email: E) -> Self {
+        self.email = Some(email.into());
+        self
+    }
+
+    /// Set the ORCID iD of the contributor, which must be well formed.
+    pub fn with_orcid<O: Into<String>>(mut self, orcid: O) -> Result<Self> {
+        let orcid = orcid.into();
+        if !is_valid_orcid(&orcid) {
+            return Err(Error::invalid_arguments(format!(
+                "not a valid ORCID iD: {}",
+                orcid
+            )));
+        }
+        self.orcid = Some(orcid);
+        Ok(self)
+    }
+
+    pub fn first_name(&self) -> &String {
+        &self.first_name
+    }
+
+    pub fn last_name(&self) -> &String {
+        &self.last_name
+    }
+
+    pub fn email(&self) -> Option<&String> {
+        self.email.as_ref()
+    }
+
+    pub fn orcid(&self) -> Option<&String> {
+        self.orcid.as_ref()
+    }
+}
+
 /// A Blackfynn dataset.
 #[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -165,11 +258,17 @@ pub struct Dataset {
     // * Unsupported
     // * Video
     package_type: Option<String>,
-    status: String,
+    status: DatasetStatus,
     automatically_process_packages: bool,
     created_at: DateTime<Utc>,
     updated_at: DateTime<Utc>,
     int_id: DatasetId,
+    #[serde(default)]
+    tags: Vec<String>,
+    license: Option<String>,
+    #[serde(default)]
+    contributors: Vec<Contributor>,
+    banner: Option<String>,
 }
 
 impl BFId for Dataset {
@@ -214,7 +313,7 @@ impl Dataset {
     }
 
     #[allow(dead_code)]
-    pub fn status(&self) -> &String {
+    pub fn status(&self) -> &DatasetStatus {
         &self.status
     }
 
@@ -232,4 +331,39 @@ impl Dataset {
     pub fn updated_at(&self) -> &DateTime<Utc> {
         &self.updated_at
     }
+
+    pub fn tags(&self) -> &Vec<String> {
+        &self.tags
+    }
+
+    pub fn license(&self) -> Option<&String> {
+        self.license.as_ref()
+    }
+
+    pub fn contributors(&self) -> &Vec<Contributor> {
+        &self.contributors
+    }
+
+    /// A temporary URL of the banner image of the dataset, if it has one.
+    pub fn banner(&self) -> Option<&String> {
+        self.banner.as_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn orcid_check_digits_are_verified() {
+        assert!(is_valid_orcid("0000-0002-1825-0097"));
+        assert!(is_valid_orcid("0000-0002-1694-233X"));
+        assert!(!is_valid_orcid("0000-0002-1825-0098"));
+        assert!(!is_valid_orcid("0000-0002-1825"));
+        assert!(!is_valid_orcid("éé-0000-0000-0000"));
+        assert!(!is_valid_orcid("0000-0002-1825-009é"));
+        assert!(Contributor::new("Josiah", "Carberry")
+            .with_orcid("0000-0002-1825-0097")
+            .is_ok());
+    }
 }
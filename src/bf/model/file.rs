@@ -12,6 +12,26 @@ pub enum FileObjectType {
     Source,
 }
 
+/// The hash of a file stored on the platform, in the scheme used by the
+/// upload service.
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileChecksum {
+    chunk_size: u64,
+    checksum: String,
+}
+
+impl FileChecksum {
+    /// The size of the chunks the file was hashed in.
+    pub fn chunk_size(&self) -> u64 {
+        self.chunk_size
+    }
+
+    pub fn checksum(&self) -> &String {
+        &self.checksum
+    }
+}
+
 /// A file on the Blackfynn platform.
 #[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -24,6 +44,8 @@ pub struct File {
     size: u64,
     created_at: String,
     updated_at: String,
+    #[serde(default)]
+    checksum: Option<FileChecksum>,
 }
 
 impl BFName for File {
@@ -76,4 +98,9 @@ impl File {
     pub fn updated_at(&self) -> &String {
         &self.updated_at
     }
+
+    /// The hash of the file, if the platform computed one.
+    pub fn checksum(&self) -> Option<&FileChecksum> {
+        self.checksum.as_ref()
+    }
 }
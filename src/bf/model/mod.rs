@@ -3,31 +3,53 @@
 //! Objects in the Blackfynn system.
 
 mod account;
+mod activity;
+mod annotation;
 mod aws;
 mod channel;
+mod concept;
 mod dataset;
 mod file;
 mod organization;
 mod package;
 mod property;
+mod publication;
+mod role;
 mod security;
+mod status;
 mod team;
 pub mod upload;
 mod user;
 
 // Re-export
 pub use self::account::SessionToken;
+pub use self::activity::{ActivityDetail, ActivityEvent, ActivityEventType};
+pub use self::annotation::{Annotation, AnnotationId, AnnotationLayer, AnnotationLayerId};
 pub use self::aws::{
     AccessKey, S3Bucket, S3EncryptionKeyId, S3Key, S3ServerSideEncryption, S3UploadId, S3UploadKey,
     SecretKey,
 };
-pub use self::channel::Channel;
-pub use self::dataset::{Dataset, DatasetId, DatasetNodeId};
-pub use self::file::File;
+pub use self::channel::{Channel, ChannelId, SampleBlock, StreamBlock};
+pub use self::concept::{
+    Concept, ConceptId, ConceptProperty, Record, RecordId, RecordRelationship, RecordValue,
+    RelationshipId, RelationshipType,
+};
+pub use self::dataset::{is_valid_orcid, Contributor, Dataset, DatasetId, DatasetNodeId};
+pub use self::file::{File, FileChecksum};
 pub use self::organization::{Organization, OrganizationId};
 pub use self::package::{Package, PackageId};
-pub use self::property::Property;
+pub use self::property::{Property, PropertyType, PropertyValue};
+pub use self::publication::{
+    publication_blockers, rejection_blockers, PublicationBlockers, PublicationRule,
+    PublicationStatus, PublicationType,
+};
+pub use self::role::Role;
 pub use self::security::{TemporaryCredential, UploadCredential};
-pub use self::team::Team;
-pub use self::upload::{FileUpload, ImportId, ManifestEntry, PackagePreview, S3File, UploadId};
+pub use self::status::{DatasetStatus, DatasetStatusOption};
+pub use self::team::{Team, TeamId};
+pub use self::upload::{
+    unique_package_name, ConflictAction, ConflictPolicy, DirectoryUploadOptions, FileUpload,
+    ImportId, ManifestEntry, PackagePreview, PlannedPackage, S3File, UploadId, UploadPlan,
+    IGNORE_FILE_NAME,
+};
 pub use self::user::{User, UserId};
@@ -125,6 +125,12 @@ impl Package {
         self.package_type.as_ref()
     }
 
+    /// Tests if the package is a collection of other packages.
+    #[allow(dead_code)]
+    pub fn is_collection(&self) -> bool {
+        self.package_type().map(String::as_str) == Some("Collection")
+    }
+
     #[allow(dead_code)]
     pub fn create_at(&self) -> &DateTime<Utc> {
         &self.created_at
@@ -2,7 +2,10 @@
 
 use std::fmt;
 
-use serde_derive::Serialize;
+use chrono::{DateTime, Utc};
+use serde_derive::{Deserialize, Serialize};
+
+use crate::bf::{Error, Result};
 
 /// A Blackfynn platform login request.
 #[derive(Clone, Hash, PartialEq, Eq, Serialize)]
@@ -23,3 +26,184 @@ impl fmt::Display for Property {
         write!(f, "({}, {})", self.key, self.value)
     }
 }
+
+/// The type of a property of a metadata concept.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub enum PropertyType {
+    String,
+    Long,
+    Double,
+    Boolean,
+    Date,
+}
+
+impl fmt::Display for PropertyType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+/// A typed value of a metadata record property.
+///
+/// The JSON form of a value does not carry its type, so decoded strings are
+/// always `String` values, even if they look like dates. Use `with_type`,
+/// or `Record::with_schema`, to type them by the schema of their concept.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(untagged, from = "UntypedValue")]
+pub enum PropertyValue {
+    Boolean(bool),
+    Long(i64),
+    Double(f64),
+    Date(DateTime<Utc>),
+    String(String),
+}
+
+impl PropertyValue {
+    /// The type of the value.
+    pub fn data_type(&self) -> PropertyType {
+        match *self {
+            PropertyValue::Boolean(_) => PropertyType::Boolean,
+            PropertyValue::Long(_) => PropertyType::Long,
+            PropertyValue::Double(_) => PropertyType::Double,
+            PropertyValue::Date(_) => PropertyType::Date,
+            PropertyValue::String(_) => PropertyType::String,
+        }
+    }
+
+    /// Convert a decoded value to the type of the property it belongs to.
+    pub fn with_type(self, data_type: PropertyType) -> Result<Self> {
+        match (self, data_type) {
+            (PropertyValue::String(s), PropertyType::Date) => s
+                .parse::<DateTime<Utc>>()
+                .map(PropertyValue::Date)
+                .map_err(|_| Error::invalid_arguments(format!("not a date: {}", s))),
+            (PropertyValue::Long(n), PropertyType::Double) => Ok(PropertyValue::Double(n as f64)),
+            (value, data_type) if value.data_type() == data_type => Ok(value),
+            (value, data_type) => Err(Error::invalid_arguments(format!(
+                "expected a {} value, got {}",
+                data_type,
+                value.data_type()
+            ))),
+        }
+    }
+
+    /// Tests if the value can be stored in a property of the given type.
+    /// Integers are accepted by `Double` properties.
+    pub fn is_compatible_with(&self, data_type: PropertyType) -> bool {
+        match (self, data_type) {
+            (PropertyValue::Long(_), PropertyType::Double) => true,
+            (value, data_type) => value.data_type() == data_type,
+        }
+    }
+}
+
+// A value as decoded, before it is typed by a schema:
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum UntypedValue {
+    Boolean(bool),
+    Long(i64),
+    Double(f64),
+    String(String),
+}
+
+impl From<UntypedValue> for PropertyValue {
+    fn from(value: UntypedValue) -> Self {
+        match value {
+            UntypedValue::Boolean(value) => PropertyValue::Boolean(value),
+            UntypedValue::Long(value) => PropertyValue::Long(value),
+            UntypedValue::Double(value) => PropertyValue::Double(value),
+            UntypedValue::String(value) => PropertyValue::String(value),
+        }
+    }
+}
+
+impl From<bool> for PropertyValue {
+    fn from(value: bool) -> Self {
+        PropertyValue::Boolean(value)
+    }
+}
+
+impl From<i32> for PropertyValue {
+    fn from(value: i32) -> Self {
+        PropertyValue::Long(i64::from(value))
+    }
+}
+
+impl From<i64> for PropertyValue {
+    fn from(value: i64) -> Self {
+        PropertyValue::Long(value)
+    }
+}
+
+impl From<f64> for PropertyValue {
+    fn from(value: f64) -> Self {
+        PropertyValue::Double(value)
+    }
+}
+
+impl From<DateTime<Utc>> for PropertyValue {
+    fn from(value: DateTime<Utc>) -> Self {
+        PropertyValue::Date(value)
+    }
+}
+
+impl From<String> for PropertyValue {
+    fn from(value: String) -> Self {
+        PropertyValue::String(value)
+    }
+}
+
+impl<'a> From<&'a str> for PropertyValue {
+    fn from(value: &'a str) -> Self {
+        PropertyValue::String(value.to_string())
+    }
+}
+
+impl fmt::Display for PropertyValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            PropertyValue::Boolean(value) => write!(f, "{}", value),
+            PropertyValue::Long(value) => write!(f, "{}", value),
+            PropertyValue::Double(value) => write!(f, "{}", value),
+            PropertyValue::Date(ref value) => write!(f, "{}", value.to_rfc3339()),
+            PropertyValue::String(ref value) => write!(f, "{}", value),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn property_values_keep_their_type() {
+        let values: Vec<PropertyValue> =
+            serde_json::from_str(r#"[true, 3, 1.5, "2019-04-01T12:00:00Z", "left"]"#).unwrap();
+        assert_eq!(
+            values.iter().map(|v| v.data_type()).collect::<Vec<_>>(),
+            vec![
+                PropertyType::Boolean,
+                PropertyType::Long,
+                PropertyType::Double,
+                PropertyType::String,
+                PropertyType::String
+            ]
+        );
+        assert_eq!(
+            values[3].clone().with_type(PropertyType::Date).unwrap(),
+            PropertyValue::Date("2019-04-01T12:00:00Z".parse().unwrap())
+        );
+        assert_eq!(
+            values[3].clone().with_type(PropertyType::String).unwrap(),
+            PropertyValue::String("2019-04-01T12:00:00Z".to_string())
+        );
+        assert_eq!(
+            values[1].clone().with_type(PropertyType::Double).unwrap(),
+            PropertyValue::Double(3.0)
+        );
+        assert!(values[4].clone().with_type(PropertyType::Date).is_err());
+        assert!(PropertyValue::from(3).is_compatible_with(PropertyType::Double));
+        assert!(!PropertyValue::from(1.5).is_compatible_with(PropertyType::Long));
+    }
+}
@@ -0,0 +1,256 @@
+// Copyright (c) 2018 Blackfynn, Inc. All Rights Reserved.
+
+use std::fmt;
+
+use serde::{Deserializer, Serializer};
+use serde_derive::{Deserialize, Serialize};
+
+use crate::bf::model::Dataset;
+
+/// The state of a request to publish a dataset.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum PublicationStatus {
+    Draft,
+    Requested,
+    Accepted,
+    Rejected,
+    Cancelled,
+    Failed,
+    Completed,
+    Unknown(String),
+}
+
+impl PublicationStatus {
+    pub fn as_str(&self) -> &str {
+        match self {
+            PublicationStatus::Draft => "draft",
+            PublicationStatus::Requested => "requested",
+            PublicationStatus::Accepted => "accepted",
+            PublicationStatus::Rejected => "rejected",
+            PublicationStatus::Cancelled => "cancelled",
+            PublicationStatus::Failed => "failed",
+            PublicationStatus::Completed => "completed",
+            PublicationStatus::Unknown(status) => status.as_str(),
+        }
+    }
+
+    /// Tests if a request is waiting to be handled, and so can be
+    /// withdrawn.
+    pub fn is_pending(&self) -> bool {
+        matches!(
+            self,
+            PublicationStatus::Requested | PublicationStatus::Accepted
+        )
+    }
+}
+
+impl fmt::Display for PublicationStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl<'a> From<&'a str> for PublicationStatus {
+    fn from(status: &'a str) -> Self {
+        match status.to_lowercase().as_str() {
+            "draft" => PublicationStatus::Draft,
+            "requested" => PublicationStatus::Requested,
+            "accepted" => PublicationStatus::Accepted,
+            "rejected" => PublicationStatus::Rejected,
+            "cancelled" => PublicationStatus::Cancelled,
+            "failed" => PublicationStatus::Failed,
+            "completed" => PublicationStatus::Completed,
+            _ => PublicationStatus::Unknown(status.to_string()),
+        }
+    }
+}
+
+impl serde::Serialize for PublicationStatus {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for PublicationStatus {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        <String as serde::Deserialize>::deserialize(deserializer)
+            .map(|s| PublicationStatus::from(s.as_str()))
+    }
+}
+
+/// The kind of a publication request.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PublicationType {
+    /// Publish the dataset immediately once accepted.
+    Publication,
+    /// Publish the dataset, but keep its files private until a release
+    /// date.
+    Embargo,
+    /// Remove a published dataset.
+    Removal,
+}
+
+impl fmt::Display for PublicationType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PublicationType::Publication => write!(f, "publication"),
+            PublicationType::Embargo => write!(f, "embargo"),
+            PublicationType::Removal => write!(f, "removal"),
+        }
+    }
+}
+
+/// A rule a dataset must satisfy before it can be published.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum PublicationRule {
+    MissingDescription,
+    MissingReadme,
+    MissingLicense,
+    MissingTags,
+    MissingContributors,
+    MissingBanner,
+    /// The embargo release date of the request is in the past.
+    EmbargoDateInPast,
+    /// The platform refused the request for the given reason.
+    Rejected(String),
+}
+
+impl fmt::Display for PublicationRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PublicationRule::MissingDescription => write!(f, "the dataset has no description"),
+            PublicationRule::MissingReadme => write!(f, "the dataset has no README"),
+            PublicationRule::MissingLicense => write!(f, "the dataset has no license"),
+            PublicationRule::MissingTags => write!(f, "the dataset has no tags"),
+            PublicationRule::MissingContributors => write!(f, "the dataset has no contributors"),
+            PublicationRule::MissingBanner => write!(f, "the dataset has no banner image"),
+            PublicationRule::EmbargoDateInPast => {
+                write!(f, "the embargo release date is in the past")
+            }
+            PublicationRule::Rejected(reason) => write!(f, "rejected by the platform: {}", reason),
+        }
+    }
+}
+
+/// The rules keeping a dataset from being published.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct PublicationBlockers(Vec<PublicationRule>);
+
+impl PublicationBlockers {
+    pub fn new(rules: Vec<PublicationRule>) -> Self {
+        PublicationBlockers(rules)
+    }
+
+    pub fn rules(&self) -> &Vec<PublicationRule> {
+        &self.0
+    }
+
+    /// Unwraps the value.
+    pub fn take(self) -> Vec<PublicationRule> {
+        self.0
+    }
+}
+
+impl fmt::Display for PublicationBlockers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let rules: Vec<String> = self.0.iter().map(ToString::to_string).collect();
+        write!(f, "{}", rules.join("; "))
+    }
+}
+
+/// List the rules keeping a dataset, with the given README, from being
+/// published.
+pub fn publication_blockers(dataset: &Dataset, readme: &str) -> Vec<PublicationRule> {
+    let mut blockers = vec![];
+    if dataset.description().is_none_or(|d| d.trim().is_empty()) {
+        blockers.push(PublicationRule::MissingDescription);
+    }
+    if readme.trim().is_empty() {
+        blockers.push(PublicationRule::MissingReadme);
+    }
+    if dataset.license().is_none() {
+        blockers.push(PublicationRule::MissingLicense);
+    }
+    if dataset.tags().is_empty() {
+        blockers.push(PublicationRule::MissingTags);
+    }
+    if dataset.contributors().is_empty() {
+        blockers.push(PublicationRule::MissingContributors);
+    }
+    if dataset.banner().is_none() {
+        blockers.push(PublicationRule::MissingBanner);
+    }
+    blockers
+}
+
+// The body of a response refusing a publication request.
+#[derive(Deserialize)]
+struct Rejection {
+    blockers: Vec<String>,
+}
+
+/// List the reasons given by the platform for refusing a publication
+/// request, or `None` if the body of its response lists none.
+pub fn rejection_blockers(body: &str) -> Option<Vec<PublicationRule>> {
+    let rejection: Rejection = serde_json::from_str(body).ok()?;
+    if rejection.blockers.is_empty() {
+        None
+    } else {
+        Some(
+            rejection
+                .blockers
+                .into_iter()
+                .map(PublicationRule::Rejected)
+                .collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn incomplete_datasets_list_their_blockers() {
+        let dataset: Dataset = serde_json::from_value(serde_json::json!({
+            "id": "N:dataset:1",
+            "name": "recordings",
+            "description": "Overnight EEG recordings",
+            "status": "IN_REVIEW",
+            "automaticallyProcessPackages": false,
+            "createdAt": "2019-04-01T12:00:00Z",
+            "updatedAt": "2019-04-01T12:00:00Z",
+            "intId": 1,
+            "tags": ["eeg"],
+            "license": "CC-BY-4.0",
+        }))
+        .unwrap();
+
+        assert_eq!(
+            publication_blockers(&dataset, ""),
+            vec![
+                PublicationRule::MissingReadme,
+                PublicationRule::MissingContributors,
+                PublicationRule::MissingBanner
+            ]
+        );
+        assert_eq!(
+            PublicationBlockers::new(publication_blockers(&dataset, "")).to_string(),
+            "the dataset has no README; the dataset has no contributors; \
+             the dataset has no banner image"
+        );
+    }
+
+    #[test]
+    fn only_rejections_listing_blockers_are_read() {
+        assert_eq!(
+            rejection_blockers(r#"{"blockers": ["the dataset is locked"]}"#),
+            Some(vec![PublicationRule::Rejected(
+                "the dataset is locked".to_string()
+            )])
+        );
+        assert_eq!(rejection_blockers(r#"{"blockers": []}"#), None);
+        assert_eq!(rejection_blockers("invalid request body"), None);
+    }
+}
@@ -0,0 +1,125 @@
+// Copyright (c) 2018 Blackfynn, Inc. All Rights Reserved.
+
+use std::cmp::Ordering;
+use std::fmt;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// A role granted to a user, team or organization on the Blackfynn
+/// platform.
+///
+/// Roles are ordered by the permissions they grant, so checks can be
+/// written as `role >= Role::Editor`. Roles not known to this client are
+/// kept as `Role::Unknown`, which grants less than any known role.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum Role {
+    Viewer,
+    Editor,
+    Manager,
+    Owner,
+    Unknown(String),
+}
+
+impl Role {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Role::Viewer => "viewer",
+            Role::Editor => "editor",
+            Role::Manager => "manager",
+            Role::Owner => "owner",
+            Role::Unknown(role) => role.as_str(),
+        }
+    }
+
+    fn rank(&self) -> u8 {
+        match self {
+            Role::Unknown(_) => 0,
+            Role::Viewer => 1,
+            Role::Editor => 2,
+            Role::Manager => 3,
+            Role::Owner => 4,
+        }
+    }
+}
+
+impl Ord for Role {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.rank()
+            .cmp(&other.rank())
+            .then_with(|| self.as_str().cmp(other.as_str()))
+    }
+}
+
+impl PartialOrd for Role {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for Role {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl<'a> From<&'a str> for Role {
+    fn from(role: &'a str) -> Self {
+        match role.to_lowercase().as_str() {
+            "viewer" => Role::Viewer,
+            "editor" => Role::Editor,
+            "manager" => Role::Manager,
+            "owner" => Role::Owner,
+            _ => Role::Unknown(role.to_string()),
+        }
+    }
+}
+
+impl From<String> for Role {
+    fn from(role: String) -> Self {
+        Role::from(role.as_str())
+    }
+}
+
+impl Serialize for Role {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for Role {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer).map(Role::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn roles_are_ordered_by_permissions() {
+        assert!(Role::Owner > Role::Manager);
+        assert!(Role::Manager > Role::Editor);
+        assert!(Role::Editor > Role::Viewer);
+        assert!(Role::Viewer > Role::Unknown("blind_reviewer".to_string()));
+        assert_eq!(
+            vec![Role::Viewer, Role::Owner, Role::Editor]
+                .into_iter()
+                .max(),
+            Some(Role::Owner)
+        );
+    }
+
+    #[test]
+    fn unknown_roles_are_kept() {
+        let roles: Vec<Role> = serde_json::from_str(r#"["Editor", "blind_reviewer"]"#).unwrap();
+        assert_eq!(
+            roles,
+            vec![Role::Editor, Role::Unknown("blind_reviewer".to_string())]
+        );
+        assert_eq!(
+            serde_json::to_string(&roles).unwrap(),
+            r#"["editor","blind_reviewer"]"#
+        );
+    }
+}
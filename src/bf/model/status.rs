@@ -0,0 +1,130 @@
+// Copyright (c) 2018 Blackfynn, Inc. All Rights Reserved.
+
+use std::fmt;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_derive::Deserialize as DeriveDeserialize;
+
+/// The status of a dataset within the workflow of its organization.
+///
+/// Organizations can configure their own statuses; statuses other than
+/// the defaults are kept as `DatasetStatus::Custom`. Statuses are parsed
+/// from either their name (ex. "IN_REVIEW") or their display name
+/// (ex. "In Review").
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub enum DatasetStatus {
+    #[default]
+    NoStatus,
+    WorkInProgress,
+    InReview,
+    Completed,
+    Custom(String),
+}
+
+impl DatasetStatus {
+    pub fn as_str(&self) -> &str {
+        match self {
+            DatasetStatus::NoStatus => "NO_STATUS",
+            DatasetStatus::WorkInProgress => "WORK_IN_PROGRESS",
+            DatasetStatus::InReview => "IN_REVIEW",
+            DatasetStatus::Completed => "COMPLETED",
+            DatasetStatus::Custom(status) => status.as_str(),
+        }
+    }
+}
+
+impl fmt::Display for DatasetStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl<'a> From<&'a str> for DatasetStatus {
+    fn from(status: &'a str) -> Self {
+        let name = status.trim().to_uppercase().replace(' ', "_");
+        match name.as_str() {
+            "NO_STATUS" => DatasetStatus::NoStatus,
+            "WORK_IN_PROGRESS" => DatasetStatus::WorkInProgress,
+            "IN_REVIEW" => DatasetStatus::InReview,
+            "COMPLETED" => DatasetStatus::Completed,
+            _ => DatasetStatus::Custom(name),
+        }
+    }
+}
+
+impl From<String> for DatasetStatus {
+    fn from(status: String) -> Self {
+        DatasetStatus::from(status.as_str())
+    }
+}
+
+impl Serialize for DatasetStatus {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for DatasetStatus {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer).map(DatasetStatus::from)
+    }
+}
+
+/// A dataset status configured by an organization.
+#[derive(Clone, Debug, DeriveDeserialize, Eq, Hash, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DatasetStatusOption {
+    id: u32,
+    name: DatasetStatus,
+    display_name: String,
+    color: Option<String>,
+    #[serde(default)]
+    in_use: bool,
+}
+
+impl DatasetStatusOption {
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    pub fn status(&self) -> &DatasetStatus {
+        &self.name
+    }
+
+    pub fn display_name(&self) -> &String {
+        &self.display_name
+    }
+
+    pub fn color(&self) -> Option<&String> {
+        self.color.as_ref()
+    }
+
+    /// Tests if any dataset of the organization has the status.
+    pub fn in_use(&self) -> bool {
+        self.in_use
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn statuses_are_parsed_from_names_and_display_names() {
+        assert_eq!(DatasetStatus::from("IN_REVIEW"), DatasetStatus::InReview);
+        assert_eq!(DatasetStatus::from("In Review"), DatasetStatus::InReview);
+        assert_eq!(DatasetStatus::from("completed"), DatasetStatus::Completed);
+        assert_eq!(
+            DatasetStatus::from("EMBARGOED"),
+            DatasetStatus::Custom("EMBARGOED".to_string())
+        );
+        assert_eq!(
+            DatasetStatus::from("Under Embargo"),
+            DatasetStatus::Custom("UNDER_EMBARGO".to_string())
+        );
+        assert_eq!(
+            serde_json::to_string(&DatasetStatus::WorkInProgress).unwrap(),
+            r#""WORK_IN_PROGRESS""#
+        );
+    }
+}
@@ -6,6 +6,7 @@ use std::ops::Deref;
 use serde_derive::{Deserialize, Serialize};
 
 use crate::bf::api::{BFId, BFName};
+use crate::bf::model;
 
 /// An identifier for a team on the Blackfynn platform.
 #[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
@@ -68,7 +69,7 @@ impl From<String> for TeamId {
 pub struct Team {
     id: TeamId,
     name: String,
-    role: Option<String>,
+    role: Option<model::Role>,
 }
 
 impl Team {
@@ -80,7 +81,7 @@ impl Team {
         &self.name
     }
 
-    pub fn role(&self) -> Option<&String> {
+    pub fn role(&self) -> Option<&model::Role> {
         self.role.as_ref()
     }
 }
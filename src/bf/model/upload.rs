@@ -3,11 +3,14 @@
 use std::borrow::Borrow;
 use std::io::{Read, Seek, SeekFrom};
 use std::path::{Path, PathBuf};
-use std::{cmp, fmt, fs, result};
+use std::{cmp, fmt, fs, result, slice, vec};
 
 use futures::*;
+use ignore::overrides::OverrideBuilder;
+use ignore::WalkBuilder;
 use serde_derive::{Deserialize, Serialize};
 
+use crate::bf::api::BFName;
 use crate::bf::util::futures::{into_future_trait, into_stream_trait};
 use crate::bf::{model, Error, Future, Result, Stream};
 
@@ -393,6 +396,162 @@ impl FileUpload {
     }
 }
 
+/// The name of the gitignore-style file that excludes paths from a
+/// recursive directory upload. It is honoured at any level of the
+/// uploaded directory tree.
+pub const IGNORE_FILE_NAME: &str = ".bfignore";
+
+/// Options controlling which files are picked up by a recursive
+/// directory upload.
+///
+/// By default hidden files are skipped, symlinks are not followed and
+/// `.bfignore` files are honoured.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct DirectoryUploadOptions {
+    include: Vec<String>,
+    exclude: Vec<String>,
+    follow_symlinks: bool,
+    include_hidden: bool,
+    use_ignore_files: bool,
+}
+
+impl Default for DirectoryUploadOptions {
+    fn default() -> Self {
+        Self {
+            include: vec![],
+            exclude: vec![],
+            follow_symlinks: false,
+            include_hidden: false,
+            use_ignore_files: true,
+        }
+    }
+}
+
+impl DirectoryUploadOptions {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Only upload files matching the given glob. If no include globs
+    /// are given, all files are considered.
+    pub fn with_include<S: Into<String>>(mut self, glob: S) -> Self {
+        self.include.push(glob.into());
+        self
+    }
+
+    /// Skip files and directories matching the given glob.
+    pub fn with_exclude<S: Into<String>>(mut self, glob: S) -> Self {
+        self.exclude.push(glob.into());
+        self
+    }
+
+    pub fn with_follow_symlinks(mut self, follow_symlinks: bool) -> Self {
+        self.follow_symlinks = follow_symlinks;
+        self
+    }
+
+    pub fn with_include_hidden(mut self, include_hidden: bool) -> Self {
+        self.include_hidden = include_hidden;
+        self
+    }
+
+    pub fn with_ignore_files(mut self, use_ignore_files: bool) -> Self {
+        self.use_ignore_files = use_ignore_files;
+        self
+    }
+
+    #[allow(dead_code)]
+    pub fn include(&self) -> &Vec<String> {
+        &self.include
+    }
+
+    #[allow(dead_code)]
+    pub fn exclude(&self) -> &Vec<String> {
+        &self.exclude
+    }
+
+    #[allow(dead_code)]
+    pub fn follow_symlinks(&self) -> bool {
+        self.follow_symlinks
+    }
+
+    #[allow(dead_code)]
+    pub fn include_hidden(&self) -> bool {
+        self.include_hidden
+    }
+
+    #[allow(dead_code)]
+    pub fn use_ignore_files(&self) -> bool {
+        self.use_ignore_files
+    }
+
+    /// Walk the directory at `path`, returning every file that passes
+    /// the filters, paired with an upload ID.
+    ///
+    /// The returned paths are relative to the parent of `path`, which
+    /// is the form expected by `Blackfynn::preview_upload` when
+    /// `is_directory_upload` is set.
+    pub fn list_files<P: AsRef<Path>>(&self, path: P) -> Result<Vec<(UploadId, PathBuf)>> {
+        let base_path = path.as_ref().canonicalize()?;
+        if !base_path.is_dir() {
+            return Err(Error::path_is_not_a_directory(base_path));
+        }
+        let parent = base_path
+            .parent()
+            .ok_or_else(|| Error::no_path_parent(base_path.to_path_buf()))?;
+
+        let invalid_glob =
+            |glob: &str, err| Error::invalid_arguments(format!("invalid glob {:?}: {}", glob, err));
+
+        let mut overrides = OverrideBuilder::new(&base_path);
+        for glob in &self.include {
+            overrides.add(glob).map_err(|err| invalid_glob(glob, err))?;
+        }
+        for glob in &self.exclude {
+            overrides
+                .add(&format!("!{}", glob))
+                .map_err(|err| invalid_glob(glob, err))?;
+        }
+
+        let overrides = overrides
+            .build()
+            .map_err(|err| Error::invalid_arguments(err.to_string()))?;
+
+        // The globs are applied as an entry filter rather than as walker
+        // overrides, as overrides would take precedence over `.bfignore`
+        // files and re-include anything they exclude.
+        let mut walker = WalkBuilder::new(&base_path);
+        walker
+            .standard_filters(false)
+            .hidden(!self.include_hidden)
+            .follow_links(self.follow_symlinks)
+            .filter_entry(move |entry| {
+                let is_dir = entry.file_type().is_some_and(|t| t.is_dir());
+                !overrides.matched(entry.path(), is_dir).is_ignore()
+            })
+            .sort_by_file_name(|a, b| a.cmp(b));
+        if self.use_ignore_files {
+            walker.add_custom_ignore_filename(IGNORE_FILE_NAME);
+        }
+
+        let mut files = vec![];
+        for entry in walker.build() {
+            let entry = entry?;
+            let is_file = entry.file_type().is_some_and(|t| t.is_file());
+            if !is_file || entry.file_name() == IGNORE_FILE_NAME {
+                continue;
+            }
+            files.push(entry.path().strip_prefix(parent)?.to_path_buf());
+        }
+
+        Ok(files
+            .into_iter()
+            .enumerate()
+            .map(|(id, file)| (UploadId::from(id as u64), file))
+            .collect())
+    }
+}
+
 /// A generic serializeable type that represents all file upload
 /// types.
 ///
@@ -536,6 +695,16 @@ impl S3File {
         self.file_path.as_ref()
     }
 
+    /// The path of the file within the upload: its destination path
+    /// followed by its name. Unlike the name, this is unique within an
+    /// import.
+    pub fn relative_path(&self) -> String {
+        match self.file_path {
+            Some(ref dirs) if !dirs.is_empty() => format!("{}/{}", dirs.join("/"), self.file_name),
+            _ => self.file_name.clone(),
+        }
+    }
+
     #[allow(dead_code)]
     pub fn read_bytes<P: AsRef<Path>>(&self, from_path: P) -> Future<Vec<u8>> {
         let file_path: PathBuf = from_path.as_ref().join(self.file_name.to_owned());
@@ -569,10 +738,31 @@ enum PayloadType {
     Workflow,
 }
 
+// The ID of a package, sent by the platform as a string or a number:
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawPackageId {
+    Text(String),
+    Number(i64),
+}
+
+fn package_id<'de, D>(deserializer: D) -> result::Result<Option<model::PackageId>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let id: Option<RawPackageId> = serde::Deserialize::deserialize(deserializer)?;
+    Ok(id.map(|id| match id {
+        RawPackageId::Text(id) => model::PackageId::new(id),
+        RawPackageId::Number(id) => model::PackageId::new(id.to_string()),
+    }))
+}
+
 // A manifest job, as generated by the Nextflow ETL processor.
 #[derive(Clone, Deserialize, Debug, Eq, Hash, PartialEq, Serialize)]
 #[serde(rename_all = "camelCase")]
 struct Payload {
+    #[serde(default, deserialize_with = "package_id")]
+    package_id: Option<model::PackageId>,
     files: Vec<String>,
 }
 
@@ -635,6 +825,12 @@ impl ManifestEntry {
     pub fn files(&self) -> &Vec<String> {
         &self.manifest.files()
     }
+
+    /// The package the files were imported into, if the platform reported
+    /// it.
+    pub fn package_id(&self) -> Option<&model::PackageId> {
+        self.manifest.content.package_id.as_ref()
+    }
 }
 
 /// A preview of a collection of files uploaded to the Blackfynn platform.
@@ -697,6 +893,179 @@ impl PackagePreview {
                     .map(|path_string| path_string.to_string())
             })
     }
+
+    /// The collections, relative to the upload destination, the package
+    /// will be placed in.
+    pub fn preview_path_components(&self) -> Vec<String> {
+        self.preview_path.clone().unwrap_or_default()
+    }
+
+    /// Tests if the files of this preview are identical to the given
+    /// source files of an existing package: each file must have the same
+    /// name, size and hash as a source file.
+    ///
+    /// `local_hash` computes the hash of a previewed file, in the scheme
+    /// used by the upload service, with the given chunk size. Source files
+    /// the platform has no hash for are never identical.
+    pub fn matches_sources<F>(&self, sources: &[model::File], local_hash: F) -> bool
+    where
+        F: Fn(&S3File, u64) -> Option<String>,
+    {
+        if self.files.len() != sources.len() {
+            return false;
+        }
+
+        let mut previewed: Vec<&S3File> = self.files.iter().collect();
+        previewed.sort_by(|a, b| (&a.file_name, a.size).cmp(&(&b.file_name, b.size)));
+        let mut existing: Vec<&model::File> = sources.iter().collect();
+        existing.sort_by(|a, b| (a.name(), a.size()).cmp(&(b.name(), b.size())));
+
+        previewed.into_iter().zip(existing).all(|(file, source)| {
+            &file.file_name == source.name()
+                && file.size == source.size()
+                && source.checksum().is_some_and(|checksum| {
+                    local_hash(file, checksum.chunk_size()).as_ref() == Some(checksum.checksum())
+                })
+        })
+    }
+}
+
+/// How to handle a previewed package whose name is already taken by a
+/// package in the upload destination.
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub enum ConflictPolicy {
+    /// Skip the package if the existing package has the same source
+    /// files (by name, size and hash), otherwise upload it alongside.
+    SkipIdentical,
+    /// Upload the package and give it a new, unique name.
+    Rename,
+    /// Upload the package, then delete the existing package.
+    Replace,
+    /// Append the files to the existing package. The upload must be
+    /// previewed and completed with `append` set.
+    Append,
+}
+
+/// What will be done with a previewed package when the upload plan
+/// is carried out.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum ConflictAction {
+    /// No conflict, or the conflicting package differs: upload as-is.
+    Upload,
+    /// An identical package already exists: nothing is sent.
+    Skip,
+    /// Upload, then rename the new package to the given name.
+    Rename(String),
+    /// Upload, then delete the conflicting packages once the upload
+    /// succeeded.
+    Replace,
+    /// Append to the first conflicting package.
+    Append,
+}
+
+/// A previewed package, along with the action to take for it.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct PlannedPackage {
+    preview: PackagePreview,
+    action: ConflictAction,
+    parent: Option<model::PackageId>,
+    conflicts: Vec<model::PackageId>,
+}
+
+impl PlannedPackage {
+    pub fn new(
+        preview: PackagePreview,
+        action: ConflictAction,
+        parent: Option<model::PackageId>,
+        conflicts: Vec<model::PackageId>,
+    ) -> Self {
+        Self {
+            preview,
+            action,
+            parent,
+            conflicts,
+        }
+    }
+
+    pub fn preview(&self) -> &PackagePreview {
+        &self.preview
+    }
+
+    pub fn action(&self) -> &ConflictAction {
+        &self.action
+    }
+
+    /// The collection the package will be placed in, if it already
+    /// exists. `None` means the dataset itself, or a collection that
+    /// will be created by the upload.
+    pub fn parent(&self) -> Option<&model::PackageId> {
+        self.parent.as_ref()
+    }
+
+    /// The existing packages with the same name in the destination.
+    pub fn conflicts(&self) -> &Vec<model::PackageId> {
+        &self.conflicts
+    }
+}
+
+/// The result of checking an upload preview against the packages
+/// already present in the upload destination.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct UploadPlan {
+    packages: Vec<PlannedPackage>,
+}
+
+impl UploadPlan {
+    pub fn new(packages: Vec<PlannedPackage>) -> Self {
+        Self { packages }
+    }
+
+    pub fn packages(&self) -> &Vec<PlannedPackage> {
+        &self.packages
+    }
+
+    /// The packages that will not be sent because an identical package
+    /// already exists.
+    pub fn skipped(&self) -> Vec<&PlannedPackage> {
+        self.packages
+            .iter()
+            .filter(|p| p.action == ConflictAction::Skip)
+            .collect()
+    }
+
+    /// The packages that will be sent.
+    pub fn to_upload(&self) -> Vec<&PlannedPackage> {
+        self.packages
+            .iter()
+            .filter(|p| p.action != ConflictAction::Skip)
+            .collect()
+    }
+
+    pub fn iter(&self) -> slice::Iter<'_, PlannedPackage> {
+        self.packages.iter()
+    }
+}
+
+impl IntoIterator for UploadPlan {
+    type Item = PlannedPackage;
+    type IntoIter = vec::IntoIter<PlannedPackage>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.packages.into_iter()
+    }
+}
+
+/// Returns `name` if it is not in `taken`, otherwise the first of
+/// "name (1)", "name (2)", ... that is not.
+pub fn unique_package_name<S: AsRef<str>>(name: &str, taken: &[S]) -> String {
+    let is_taken = |candidate: &str| taken.iter().any(|t| t.as_ref() == candidate);
+    if !is_taken(name) {
+        return name.to_string();
+    }
+    (1..)
+        .map(|n| format!("{} ({})", name, n))
+        .find(|candidate| !is_taken(candidate))
+        .unwrap()
 }
 
 #[cfg(test)]
@@ -704,6 +1073,8 @@ mod tests {
     use super::*;
     use std::fs::File;
 
+    use crate::bf::ErrorKind;
+
     const USE_CHUNK_SIZE: u64 = 100;
 
     #[test]
@@ -769,4 +1140,147 @@ mod tests {
             Ok(s3_file) => assert!(s3_file.file_path == None),
         }
     }
+
+    fn filtered_files(options: &DirectoryUploadOptions) -> Vec<String> {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/test/data/filtered").to_owned();
+        options
+            .list_files(path)
+            .unwrap()
+            .into_iter()
+            .map(|(_, file)| file.to_str().unwrap().replace("\\", "/"))
+            .collect()
+    }
+
+    #[test]
+    pub fn directory_listing_honours_ignore_files_and_skips_hidden_files() {
+        let files = filtered_files(&DirectoryUploadOptions::new());
+        assert_eq!(
+            files,
+            vec![
+                "filtered/nested/notes.txt",
+                "filtered/nested/session.csv",
+                "filtered/recording.csv",
+            ]
+        );
+    }
+
+    #[test]
+    pub fn directory_listing_applies_include_and_exclude_globs() {
+        let options = DirectoryUploadOptions::new()
+            .with_include("*.csv")
+            .with_exclude("nested/session.csv");
+        assert_eq!(filtered_files(&options), vec!["filtered/recording.csv"]);
+    }
+
+    #[test]
+    pub fn directory_listing_rejects_invalid_globs() {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/test/data/filtered").to_owned();
+        let err = DirectoryUploadOptions::new()
+            .with_exclude("raw/[")
+            .list_files(path)
+            .unwrap_err();
+        match err.kind() {
+            ErrorKind::InvalidArguments { message } => assert!(message.contains("raw/[")),
+            kind => panic!("unexpected error: {}", kind),
+        }
+    }
+
+    #[test]
+    pub fn directory_listing_can_include_hidden_and_ignored_files() {
+        let options = DirectoryUploadOptions::new()
+            .with_include_hidden(true)
+            .with_ignore_files(false);
+        let files = filtered_files(&options);
+        assert!(files.contains(&"filtered/.DS_Store".to_string()));
+        assert!(files.contains(&"filtered/raw/intermediate.bin".to_string()));
+        assert!(files.contains(&"filtered/nested/skip.csv".to_string()));
+        assert!(!files.iter().any(|file| file.ends_with(IGNORE_FILE_NAME)));
+    }
+
+    #[test]
+    pub fn directory_listing_upload_ids_are_unique() {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/test/data/filtered").to_owned();
+        let files = DirectoryUploadOptions::new().list_files(path).unwrap();
+        let ids: Vec<u64> = files.iter().map(|(id, _)| id.take()).collect();
+        assert_eq!(ids, vec![0, 1, 2]);
+    }
+
+    #[test]
+    pub fn manifests_report_the_package_imported_into() {
+        let manifest = |package_id: serde_json::Value| -> ManifestEntry {
+            serde_json::from_value(serde_json::json!({
+                "manifest": {
+                    "type": "upload",
+                    "importId": "import id",
+                    "content": { "packageId": package_id, "files": ["a.csv"] }
+                }
+            }))
+            .unwrap()
+        };
+        assert_eq!(
+            manifest(serde_json::json!("N:package:1")).package_id(),
+            Some(&model::PackageId::new("N:package:1"))
+        );
+        assert_eq!(
+            manifest(serde_json::json!(42)).package_id(),
+            Some(&model::PackageId::new("42"))
+        );
+        assert_eq!(manifest(serde_json::Value::Null).package_id(), None);
+    }
+
+    #[test]
+    pub fn unique_package_names_skip_taken_names() {
+        let taken = vec!["brain", "brain (1)", "notes"];
+        assert_eq!(unique_package_name("scan", &taken), "scan");
+        assert_eq!(unique_package_name("notes", &taken), "notes (1)");
+        assert_eq!(unique_package_name("brain", &taken), "brain (2)");
+    }
+
+    #[test]
+    pub fn package_previews_match_sources_by_name_size_and_hash() {
+        let preview: PackagePreview = serde_json::from_str(
+            r#"{
+                "packageName": "example",
+                "packageType": "CSV",
+                "fileType": "CSV",
+                "importId": "import id",
+                "files": [{"fileName": "example.csv", "size": 1117}],
+                "groupSize": 1
+            }"#,
+        )
+        .unwrap();
+        let source = |name: &str, size: u64, checksum: Option<&str>| -> model::File {
+            serde_json::from_value(serde_json::json!({
+                "name": name,
+                "fileType": "CSV",
+                "s3bucket": "bucket",
+                "s3key": "key",
+                "objectType": "source",
+                "size": size,
+                "createdAt": "2019-01-01T00:00:00Z",
+                "updatedAt": "2019-01-01T00:00:00Z",
+                "checksum": checksum.map(|c| serde_json::json!({
+                    "chunkSize": 100,
+                    "checksum": c
+                }))
+            }))
+            .unwrap()
+        };
+        let local_hash = |_: &S3File, chunk_size: u64| {
+            assert_eq!(chunk_size, 100);
+            Some("abc".to_string())
+        };
+
+        assert!(preview.matches_sources(&[source("example.csv", 1117, Some("abc"))], local_hash));
+        assert!(!preview.matches_sources(&[source("example.csv", 1117, Some("def"))], local_hash));
+        assert!(!preview.matches_sources(&[source("example.csv", 1117, None)], local_hash));
+        assert!(!preview.matches_sources(&[source("example.csv", 1000, Some("abc"))], local_hash));
+        assert!(!preview.matches_sources(
+            &[
+                source("example.csv", 1117, Some("abc")),
+                source("other.csv", 1117, Some("abc"))
+            ],
+            local_hash
+        ));
+    }
 }
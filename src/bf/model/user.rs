@@ -70,7 +70,7 @@ pub struct User {
     last_name: String,
     email: String,
     preferred_organization: Option<model::OrganizationId>,
-    role: Option<String>,
+    role: Option<model::Role>,
 }
 
 impl BFId for User {
@@ -101,7 +101,7 @@ impl User {
         self.preferred_organization.as_ref()
     }
 
-    pub fn role(&self) -> Option<&String> {
+    pub fn role(&self) -> Option<&model::Role> {
         self.role.as_ref()
     }
 }